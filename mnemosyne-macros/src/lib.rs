@@ -17,14 +17,56 @@ use syn::{
 /// }
 /// ```
 ///
-/// This will expand to code that calls `mnemosyne.protect(id, || async { ... })`.
+/// This will expand to code that calls `mnemosyne.protect(id, || async { ... })`, moving
+/// the function's own parameters into that closure. Since `Mnemosyne::protect` takes
+/// `FnOnce`, the body runs at most once per call - there is no generated expansion that
+/// retries it, so `Config::retry_policy` has no effect on a `#[protect]`-annotated
+/// function. Call `Mnemosyne::protect_retrying` by hand if you need the body retried.
+///
+/// Pass `content = payload_expr` instead of `id = ...` to derive the idempotency id
+/// from the payload itself via `Mnemosyne::protect_content`, rather than supplying one:
+///
+/// ```rust,ignore
+/// #[protect(mnemosyne = self.cache, content = email)]
+/// async fn send_email(&self, email: Email) -> Result<String, Error> {
+///     Ok("sent".to_string())
+/// }
+/// ```
+///
+/// `id` also accepts a bracketed list of expressions, combined into a single composite
+/// key (a tuple) so multi-field idempotency doesn't require manually constructing one:
+///
+/// ```rust,ignore
+/// #[protect(mnemosyne = self.cache, id = [tenant_id, email.id])]
+/// async fn send_email(&self, tenant_id: Uuid, email: Email) -> Result<String, Error> {
+///     Ok("sent".to_string())
+/// }
+/// ```
+///
+/// Pass `on_duplicate = handler` to observe whether a call executed fresh or hit an
+/// existing claim. When present, the expansion calls `try_start_process` instead of
+/// `protect`, runs the function body only for `Outcome::New`, and routes
+/// `Outcome::Duplicate { value }` through `handler(value)` instead - so callers can log,
+/// increment a metric, or transform the cached result. `handler` must evaluate to a
+/// function or closure with signature `fn(A) -> Result<A, Error>`.
+///
+/// ```rust,ignore
+/// #[protect(mnemosyne = self.cache, id = email.id, on_duplicate = Self::log_duplicate_email)]
+/// async fn send_email(&self, email: Email) -> Result<String, Error> {
+///     Ok("sent".to_string())
+/// }
+/// ```
 ///
 /// # Requirements
 ///
 /// - The function must be `async`
 /// - The function must return `Result<A, Error>` where `A` matches the type parameter of `Mnemosyne<A>`
 /// - `A` must implement `Clone`
-/// - The `id` expression must evaluate to a type that implements `Into<Id>`
+/// - Exactly one of `id` or `content` must be given.
+/// - `id` must evaluate to the `Id` type of the `Mnemosyne` instance (as a bracketed list,
+///   `Id` must be the tuple of the listed expressions' types); it is passed as-is, with no
+///   conversion. `content` must evaluate to a `Serialize` payload, with `Id: From<String>`.
+/// - `on_duplicate`, if given, must evaluate to a `fn(A) -> Result<A, Error>`.
 #[proc_macro_attribute]
 pub fn protect(attr: TokenStream, item: TokenStream) -> TokenStream {
     let input = parse_macro_input!(item as ItemFn);
@@ -39,6 +81,8 @@ pub fn protect(attr: TokenStream, item: TokenStream) -> TokenStream {
     // Parse the attribute arguments
     let mut mnemosyne_expr = None;
     let mut id_expr = None;
+    let mut content_expr = None;
+    let mut on_duplicate_expr = None;
 
     for arg in args {
         match arg {
@@ -49,12 +93,26 @@ pub fn protect(attr: TokenStream, item: TokenStream) -> TokenStream {
                         mnemosyne_expr = Some(nv.value);
                     }
                     Some("id") => {
-                        id_expr = Some(nv.value);
+                        // A bracketed list (`id = [tenant, email.id]`) combines into a
+                        // single composite key; a bare expression is used as-is.
+                        id_expr = Some(match nv.value {
+                            syn::Expr::Array(array) => {
+                                let elems = array.elems;
+                                syn::parse_quote!((#elems))
+                            }
+                            other => other,
+                        });
+                    }
+                    Some("content") => {
+                        content_expr = Some(nv.value);
+                    }
+                    Some("on_duplicate") => {
+                        on_duplicate_expr = Some(nv.value);
                     }
                     _ => {
                         return syn::Error::new_spanned(
                             nv.path,
-                            "Unknown attribute parameter. Expected 'mnemosyne' or 'id'",
+                            "Unknown attribute parameter. Expected 'mnemosyne', 'id', 'content' or 'on_duplicate'",
                         )
                         .to_compile_error()
                         .into();
@@ -81,12 +139,21 @@ pub fn protect(attr: TokenStream, item: TokenStream) -> TokenStream {
         }
     };
 
-    let id = match id_expr {
-        Some(expr) => expr,
-        None => {
+    let dedup_key = match (id_expr, content_expr) {
+        (Some(id), None) => DedupKey::Id(id),
+        (None, Some(content)) => DedupKey::Content(content),
+        (Some(_), Some(_)) => {
+            return syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "Expected exactly one of 'id' or 'content', not both",
+            )
+            .to_compile_error()
+            .into();
+        }
+        (None, None) => {
             return syn::Error::new(
                 proc_macro2::Span::call_site(),
-                "Missing required 'id' parameter",
+                "Missing required 'id' or 'content' parameter",
             )
             .to_compile_error()
             .into();
@@ -139,18 +206,60 @@ pub fn protect(attr: TokenStream, item: TokenStream) -> TokenStream {
     };
 
     // Generate the expanded function
-    let expanded = quote! {
-        #(#fn_attrs)*
-        #fn_vis async fn #fn_name #fn_generics(#fn_inputs) -> #return_type {
+    let dedup_call = match (dedup_key, on_duplicate_expr) {
+        (DedupKey::Id(id), None) => quote! {
             let __mnemosyne_id = #id;
-            let __mnemosyne = #mnemosyne;
-
             __mnemosyne.protect(__mnemosyne_id, || async move {
                 #(let #param_names = #param_names;)*
                 #fn_block
             }).await
+        },
+        (DedupKey::Content(content), None) => quote! {
+            let __mnemosyne_payload = &(#content);
+            __mnemosyne.protect_content(__mnemosyne_payload, || async move {
+                #(let #param_names = #param_names;)*
+                #fn_block
+            }).await
+        },
+        (DedupKey::Id(id), Some(on_duplicate)) => quote! {
+            let __mnemosyne_id = #id;
+            match __mnemosyne.try_start_process(__mnemosyne_id).await? {
+                ::mnemosyne_rs::Outcome::New { complete_process } => {
+                    let __mnemosyne_result = (async move {
+                        #(let #param_names = #param_names;)*
+                        #fn_block
+                    }).await;
+                    match __mnemosyne_result {
+                        Ok(value) => complete_process(value.clone()).await.map(|_| value),
+                        Err(err) => Err(err),
+                    }
+                }
+                ::mnemosyne_rs::Outcome::Duplicate { value } => (#on_duplicate)(value),
+            }
+        },
+        (DedupKey::Content(_), Some(_)) => {
+            return syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "'on_duplicate' requires 'id', not 'content' - protect_content has no try_start variant",
+            )
+            .to_compile_error()
+            .into();
+        }
+    };
+
+    let expanded = quote! {
+        #(#fn_attrs)*
+        #fn_vis async fn #fn_name #fn_generics(#fn_inputs) -> #return_type {
+            let __mnemosyne = #mnemosyne;
+            #dedup_call
         }
     };
 
     TokenStream::from(expanded)
 }
+
+/// Which expression the macro derives the backend claim key from.
+enum DedupKey {
+    Id(syn::Expr),
+    Content(syn::Expr),
+}
@@ -0,0 +1,86 @@
+use crate::error::Error;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Pluggable serialization for the `memoized` value a backend persists.
+///
+/// Backends call this instead of hard-coding `serde_json`, so callers can swap in a
+/// compact binary format (CBOR, MessagePack) for large memoized results or
+/// schema-evolution needs, while the default stays plain, human-readable JSON.
+pub trait ValueCodec<A>: Send + Sync {
+    fn encode(&self, value: &A) -> Result<Vec<u8>, Error>;
+    fn decode(&self, bytes: &[u8]) -> Result<A, Error>;
+
+    /// Whether encoded bytes are an opaque binary blob (`true` stores as a native binary
+    /// attribute) or human-readable text (`false` stores as a native string attribute).
+    /// Defaults to text, matching [`JsonCodec`].
+    fn is_binary(&self) -> bool {
+        false
+    }
+}
+
+/// Default codec: plain JSON via `serde_json`, matching the format backends used before
+/// the codec abstraction existed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonCodec;
+
+impl<A> ValueCodec<A> for JsonCodec
+where
+    A: Serialize + DeserializeOwned,
+{
+    fn encode(&self, value: &A) -> Result<Vec<u8>, Error> {
+        Ok(serde_json::to_vec(value)?)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<A, Error> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
+/// Compact, self-describing binary codec via CBOR (`ciborium`). Reduces item size and
+/// (de)serialization cost for large memoized values, and tolerates schema evolution
+/// better than a positional binary format.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CborCodec;
+
+impl<A> ValueCodec<A> for CborCodec
+where
+    A: Serialize + DeserializeOwned,
+{
+    fn encode(&self, value: &A) -> Result<Vec<u8>, Error> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(value, &mut bytes)
+            .map_err(|e| Error::Encoding(e.to_string()))?;
+        Ok(bytes)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<A, Error> {
+        ciborium::from_reader(bytes).map_err(|e| Error::Decoding(e.to_string()))
+    }
+
+    fn is_binary(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_codec_roundtrip() {
+        let codec = JsonCodec;
+        let bytes = ValueCodec::<String>::encode(&codec, &"hello".to_string()).unwrap();
+        let value: String = codec.decode(&bytes).unwrap();
+        assert_eq!(value, "hello");
+        assert!(!codec.is_binary());
+    }
+
+    #[test]
+    fn test_cbor_codec_roundtrip() {
+        let codec = CborCodec;
+        let bytes = ValueCodec::<String>::encode(&codec, &"hello".to_string()).unwrap();
+        let value: String = codec.decode(&bytes).unwrap();
+        assert_eq!(value, "hello");
+        assert!(codec.is_binary());
+    }
+}
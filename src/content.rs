@@ -0,0 +1,73 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// Pluggable content-addressing hash, used by [`crate::Mnemosyne::protect_content`] to
+/// derive a deterministic idempotency id from a signal's payload instead of requiring
+/// callers to mint one themselves.
+///
+/// `key` is [`crate::model::Config::content_hash_key`] - keying the digest (rather than
+/// hashing the payload alone) means two tenants with different keys that happen to submit
+/// byte-identical payloads don't collide on the same derived id, and someone without the
+/// key can't guess/forge ids from payload content alone.
+pub trait ContentHasher: Send + Sync {
+    fn digest(&self, key: &[u8], payload: &[u8]) -> Vec<u8>;
+}
+
+/// Default [`ContentHasher`]: HMAC-SHA256.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HmacSha256Hasher;
+
+impl ContentHasher for HmacSha256Hasher {
+    fn digest(&self, key: &[u8], payload: &[u8]) -> Vec<u8> {
+        // `Hmac::<Sha256>::new_from_slice` only fails for key lengths an HMAC
+        // implementation rejects, which `Sha256`'s block size never does.
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+        mac.update(payload);
+        mac.finalize().into_bytes().to_vec()
+    }
+}
+
+/// Renders `bytes` as a lowercase hex string, for turning a [`ContentHasher`] digest into
+/// a human-inspectable `Id`.
+pub(crate) fn hex_encode(bytes: &[u8]) -> String {
+    use std::fmt::Write;
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        write!(out, "{byte:02x}").expect("writing to a String never fails");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hmac_sha256_hasher_is_deterministic() {
+        let hasher = HmacSha256Hasher;
+        let a = hasher.digest(b"key", b"payload");
+        let b = hasher.digest(b"key", b"payload");
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_hmac_sha256_hasher_differs_by_key() {
+        let hasher = HmacSha256Hasher;
+        let a = hasher.digest(b"key-a", b"payload");
+        let b = hasher.digest(b"key-b", b"payload");
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_hmac_sha256_hasher_differs_by_payload() {
+        let hasher = HmacSha256Hasher;
+        let a = hasher.digest(b"key", b"payload-a");
+        let b = hasher.digest(b"key", b"payload-b");
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_hex_encode() {
+        assert_eq!(hex_encode(&[0x00, 0xab, 0xff]), "00abff");
+    }
+}
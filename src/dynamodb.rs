@@ -1,32 +1,60 @@
+use crate::codec::{JsonCodec, ValueCodec};
 use crate::error::Error;
 use crate::model::{Expiration, Process};
 use crate::persistence::Persistence;
 use async_trait::async_trait;
-use aws_sdk_dynamodb::types::AttributeValue;
+use aws_sdk_dynamodb::types::{AttributeValue, Put, TransactWriteItem};
 use aws_sdk_dynamodb::Client;
 use serde::{de::DeserializeOwned, Serialize};
 use std::collections::HashMap;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-/// DynamoDB-backed persistence implementation
-pub struct DynamoDbPersistence {
+#[cfg(feature = "tracing")]
+use tracing::instrument;
+
+/// Maximum number of items DynamoDB allows in a single `TransactWriteItems` call.
+const TRANSACT_WRITE_CHUNK_SIZE: usize = 25;
+
+/// DynamoDB-backed persistence implementation.
+///
+/// `C` controls how the `memoized` value is serialized (see [`ValueCodec`]); it defaults
+/// to [`JsonCodec`] so existing callers keep today's plain-JSON item layout.
+pub struct DynamoDbPersistence<C = JsonCodec> {
     client: Client,
     table_name: String,
+    codec: C,
 }
 
-impl DynamoDbPersistence {
+impl DynamoDbPersistence<JsonCodec> {
     pub fn new(client: Client, table_name: String) -> Self {
-        Self { client, table_name }
+        Self {
+            client,
+            table_name,
+            codec: JsonCodec,
+        }
+    }
+}
+
+impl<C> DynamoDbPersistence<C> {
+    /// Use a non-default codec (e.g. [`crate::codec::CborCodec`]) for the memoized value.
+    pub fn with_codec(client: Client, table_name: String, codec: C) -> Self {
+        Self {
+            client,
+            table_name,
+            codec,
+        }
     }
 }
 
 #[async_trait]
-impl<Id, ProcessorId, A> Persistence<Id, ProcessorId, A> for DynamoDbPersistence
+impl<Id, ProcessorId, A, C> Persistence<Id, ProcessorId, A> for DynamoDbPersistence<C>
 where
     Id: Serialize + DeserializeOwned + Send + Sync + Clone + 'static,
     ProcessorId: Serialize + DeserializeOwned + Send + Sync + Clone + 'static,
     A: Serialize + DeserializeOwned + Send + Sync + Clone + 'static,
+    C: ValueCodec<A> + 'static,
 {
+    #[cfg_attr(feature = "tracing", instrument(skip(self, id, processor_id)))]
     async fn start_processing_update(
         &self,
         id: Id,
@@ -55,13 +83,64 @@ where
 
         if let Some(attributes) = result.attributes {
             if !attributes.is_empty() {
-                return Ok(Some(decode_process(attributes)?));
+                return Ok(Some(decode_process(attributes, &self.codec)?));
             }
         }
 
         Ok(None)
     }
 
+    #[cfg_attr(feature = "tracing", instrument(skip(self, id, processor_id)))]
+    async fn reclaim_process(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        now: SystemTime,
+        expected_claim_token: SystemTime,
+    ) -> Result<(), Error> {
+        let id_str = serde_json::to_string(&id)?;
+        let processor_id_str = serde_json::to_string(&processor_id)?;
+        let now_millis = now
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| Error::Internal(e.to_string()))?
+            .as_millis() as i64;
+        let expected_millis = expected_claim_token
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| Error::Internal(e.to_string()))?
+            .as_millis() as i64;
+
+        // Unlike `start_processing_update`'s `if_not_exists`, this unconditionally
+        // overwrites `startedAt` - but only when it still equals the stale value this
+        // caller observed, so a concurrent reclaimer of the same claim can't also win.
+        let result = self
+            .client
+            .update_item()
+            .table_name(&self.table_name)
+            .key("id", AttributeValue::S(id_str))
+            .key("processorId", AttributeValue::S(processor_id_str))
+            .update_expression("SET startedAt = :now")
+            .condition_expression("startedAt = :expected")
+            .expression_attribute_values(":now", AttributeValue::N(now_millis.to_string()))
+            .expression_attribute_values(":expected", AttributeValue::N(expected_millis.to_string()))
+            .send()
+            .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                let claim_lost = err
+                    .as_service_error()
+                    .is_some_and(|se| se.is_conditional_check_failed_exception());
+                if claim_lost {
+                    Err(Error::ClaimLost)
+                } else {
+                    Err(Error::DynamoDb(err.to_string()))
+                }
+            }
+        }
+    }
+
+    #[cfg_attr(feature = "tracing", instrument(skip(self, id, processor_id, value)))]
     async fn complete_process(
         &self,
         id: Id,
@@ -69,6 +148,7 @@ where
         now: SystemTime,
         ttl: Option<Duration>,
         value: A,
+        claim_token: Option<SystemTime>,
     ) -> Result<(), Error> {
         let id_str = serde_json::to_string(&id)?;
         let processor_id_str = serde_json::to_string(&processor_id)?;
@@ -77,7 +157,14 @@ where
             .map_err(|e| Error::Internal(e.to_string()))?
             .as_millis() as i64;
 
-        let memoized_str = serde_json::to_string(&value)?;
+        let memoized_bytes = self.codec.encode(&value)?;
+        let memoized_attribute = if self.codec.is_binary() {
+            AttributeValue::B(aws_sdk_dynamodb::primitives::Blob::new(memoized_bytes))
+        } else {
+            let memoized_str = String::from_utf8(memoized_bytes)
+                .map_err(|e| Error::Encoding(e.to_string()))?;
+            AttributeValue::S(memoized_str)
+        };
 
         let mut update_builder = self
             .client
@@ -86,7 +173,7 @@ where
             .key("id", AttributeValue::S(id_str))
             .key("processorId", AttributeValue::S(processor_id_str))
             .expression_attribute_values(":completedAt", AttributeValue::N(now_millis.to_string()))
-            .expression_attribute_values(":memoized", AttributeValue::S(memoized_str));
+            .expression_attribute_values(":memoized", memoized_attribute);
 
         let update_expr = if let Some(ttl_duration) = ttl {
             let expires_on = (now + ttl_duration)
@@ -104,40 +191,285 @@ where
             "SET completedAt = :completedAt, memoized = :memoized"
         };
 
-        update_builder
-            .update_expression(update_expr)
-            .send()
-            .await
-            .map_err(|e| Error::DynamoDb(e.to_string()))?;
+        if let Some(token) = claim_token {
+            let token_millis = token
+                .duration_since(UNIX_EPOCH)
+                .map_err(|e| Error::Internal(e.to_string()))?
+                .as_millis() as i64;
+
+            update_builder = update_builder
+                .condition_expression("startedAt = :claimToken AND attribute_not_exists(completedAt)")
+                .expression_attribute_values(":claimToken", AttributeValue::N(token_millis.to_string()));
+        }
+
+        let result = update_builder.update_expression(update_expr).send().await;
 
-        Ok(())
+        match result {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                let claim_lost = err
+                    .as_service_error()
+                    .is_some_and(|se| se.is_conditional_check_failed_exception());
+                if claim_lost {
+                    Err(Error::ClaimLost)
+                } else {
+                    Err(Error::DynamoDb(err.to_string()))
+                }
+            }
+        }
     }
 
-    async fn invalidate_process(&self, id: Id, processor_id: ProcessorId) -> Result<(), Error> {
+    #[cfg_attr(feature = "tracing", instrument(skip(self, id, processor_id, reason)))]
+    async fn fail_process(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        now: SystemTime,
+        attempt: u32,
+        reason: String,
+        claim_token: Option<SystemTime>,
+    ) -> Result<(), Error> {
         let id_str = serde_json::to_string(&id)?;
         let processor_id_str = serde_json::to_string(&processor_id)?;
+        let now_millis = now
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| Error::Internal(e.to_string()))?
+            .as_millis() as i64;
 
-        self.client
+        let mut update_builder = self
+            .client
+            .update_item()
+            .table_name(&self.table_name)
+            .key("id", AttributeValue::S(id_str))
+            .key("processorId", AttributeValue::S(processor_id_str))
+            .update_expression("SET failedAt = :failedAt, attempt = :attempt, failureReason = :reason")
+            .expression_attribute_values(":failedAt", AttributeValue::N(now_millis.to_string()))
+            .expression_attribute_values(":attempt", AttributeValue::N(attempt.to_string()))
+            .expression_attribute_values(":reason", AttributeValue::S(reason));
+
+        if let Some(token) = claim_token {
+            let token_millis = token
+                .duration_since(UNIX_EPOCH)
+                .map_err(|e| Error::Internal(e.to_string()))?
+                .as_millis() as i64;
+
+            update_builder = update_builder
+                .condition_expression("startedAt = :claimToken AND attribute_not_exists(completedAt)")
+                .expression_attribute_values(":claimToken", AttributeValue::N(token_millis.to_string()));
+        }
+
+        let result = update_builder.send().await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                let claim_lost = err
+                    .as_service_error()
+                    .is_some_and(|se| se.is_conditional_check_failed_exception());
+                if claim_lost {
+                    Err(Error::ClaimLost)
+                } else {
+                    Err(Error::DynamoDb(err.to_string()))
+                }
+            }
+        }
+    }
+
+    #[cfg_attr(feature = "tracing", instrument(skip(self, id, processor_id)))]
+    async fn invalidate_process(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        claim_token: Option<SystemTime>,
+    ) -> Result<(), Error> {
+        let id_str = serde_json::to_string(&id)?;
+        let processor_id_str = serde_json::to_string(&processor_id)?;
+
+        let mut delete_builder = self
+            .client
             .delete_item()
             .table_name(&self.table_name)
             .key("id", AttributeValue::S(id_str))
+            .key("processorId", AttributeValue::S(processor_id_str));
+
+        if let Some(token) = claim_token {
+            let token_millis = token
+                .duration_since(UNIX_EPOCH)
+                .map_err(|e| Error::Internal(e.to_string()))?
+                .as_millis() as i64;
+
+            delete_builder = delete_builder
+                .condition_expression("startedAt = :claimToken")
+                .expression_attribute_values(":claimToken", AttributeValue::N(token_millis.to_string()));
+        }
+
+        let result = delete_builder.send().await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                let claim_lost = err
+                    .as_service_error()
+                    .is_some_and(|se| se.is_conditional_check_failed_exception());
+                if claim_lost {
+                    Err(Error::ClaimLost)
+                } else {
+                    Err(Error::DynamoDb(err.to_string()))
+                }
+            }
+        }
+    }
+
+    /// Refreshes `heartbeatAt` on a claimed record, guarded by `attribute_exists(id)` so
+    /// that a record already invalidated (deleted) is left alone instead of being
+    /// resurrected by this unconditional-looking `update_item`; a completed record still
+    /// exists and has its `heartbeatAt` refreshed like any other. When `claim_token` is
+    /// set, the update is additionally guarded on `startedAt` still matching it, so a
+    /// heartbeat task left running past its claim (another processor reclaimed the
+    /// record after this one fell behind) reports `Error::ClaimLost` instead of
+    /// resurrecting liveness for a process it no longer owns.
+    #[cfg_attr(feature = "tracing", instrument(skip(self, id, processor_id)))]
+    async fn heartbeat(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        now: SystemTime,
+        claim_token: Option<SystemTime>,
+    ) -> Result<(), Error> {
+        let id_str = serde_json::to_string(&id)?;
+        let processor_id_str = serde_json::to_string(&processor_id)?;
+        let now_millis = now
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| Error::Internal(e.to_string()))?
+            .as_millis() as i64;
+
+        let mut update_builder = self
+            .client
+            .update_item()
+            .table_name(&self.table_name)
+            .key("id", AttributeValue::S(id_str))
             .key("processorId", AttributeValue::S(processor_id_str))
-            .send()
-            .await
-            .map_err(|e| Error::DynamoDb(e.to_string()))?;
+            .update_expression("SET heartbeatAt = :value")
+            .expression_attribute_values(":value", AttributeValue::N(now_millis.to_string()));
 
-        Ok(())
+        update_builder = if let Some(token) = claim_token {
+            let token_millis = token
+                .duration_since(UNIX_EPOCH)
+                .map_err(|e| Error::Internal(e.to_string()))?
+                .as_millis() as i64;
+
+            update_builder
+                .condition_expression("attribute_exists(id) AND startedAt = :claimToken")
+                .expression_attribute_values(":claimToken", AttributeValue::N(token_millis.to_string()))
+        } else {
+            update_builder.condition_expression("attribute_exists(id)")
+        };
+
+        let result = update_builder.send().await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                let condition_failed = err
+                    .as_service_error()
+                    .is_some_and(|se| se.is_conditional_check_failed_exception());
+                if condition_failed {
+                    if claim_token.is_some() {
+                        // The record moved on to a different claim - stop heartbeating it.
+                        Err(Error::ClaimLost)
+                    } else {
+                        // Already invalidated (deleted) - nothing left to heartbeat.
+                        Ok(())
+                    }
+                } else {
+                    Err(Error::DynamoDb(err.to_string()))
+                }
+            }
+        }
+    }
+
+    /// Claims a batch of ids via `TransactWriteItems`, chunked to DynamoDB's 25-item
+    /// transaction limit. Each item is a conditional `Put` of a fresh record guarded by
+    /// `attribute_not_exists(id)`, so a whole chunk either claims every brand-new id
+    /// atomically or is cancelled. On cancellation (some ids already had a record) we
+    /// fall back to per-item `start_processing_update` calls for just that chunk, which
+    /// mirrors the "retry the unprocessed items individually" pattern `BatchWriteItem`
+    /// callers use for its `UnprocessedItems`.
+    #[cfg_attr(feature = "tracing", instrument(skip(self, ids, processor_id), fields(batch_size = ids.len())))]
+    async fn start_processing_batch(
+        &self,
+        ids: Vec<Id>,
+        processor_id: ProcessorId,
+        now: SystemTime,
+    ) -> Result<Vec<(Id, Option<Process<Id, ProcessorId, A>>)>, Error> {
+        let now_millis = now
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| Error::Internal(e.to_string()))?
+            .as_millis() as i64;
+        let processor_id_str = serde_json::to_string(&processor_id)?;
+
+        let mut results = Vec::with_capacity(ids.len());
+
+        for chunk in ids.chunks(TRANSACT_WRITE_CHUNK_SIZE) {
+            let mut transact_items = Vec::with_capacity(chunk.len());
+            for id in chunk {
+                let id_str = serde_json::to_string(id)?;
+                let put = Put::builder()
+                    .table_name(&self.table_name)
+                    .item("id", AttributeValue::S(id_str))
+                    .item("processorId", AttributeValue::S(processor_id_str.clone()))
+                    .item("startedAt", AttributeValue::N(now_millis.to_string()))
+                    .condition_expression("attribute_not_exists(id)")
+                    .build()
+                    .map_err(|e| Error::DynamoDb(e.to_string()))?;
+                transact_items.push(TransactWriteItem::builder().put(put).build());
+            }
+
+            let claimed = self
+                .client
+                .transact_write_items()
+                .set_transact_items(Some(transact_items))
+                .send()
+                .await;
+
+            match claimed {
+                Ok(_) => {
+                    // Every id in the chunk was brand new - no prior record to report.
+                    for id in chunk {
+                        results.push((id.clone(), None));
+                    }
+                }
+                Err(_) => {
+                    // At least one id in the chunk already had a record, so the whole
+                    // transaction was cancelled. Fall back to the per-item path, which
+                    // reports the correct prior record for ids that already existed and
+                    // still claims the genuinely new ones.
+                    for id in chunk {
+                        let previous = self
+                            .start_processing_update(id.clone(), processor_id.clone(), now)
+                            .await?;
+                        results.push((id.clone(), previous));
+                    }
+                }
+            }
+        }
+
+        Ok(results)
     }
 }
 
-/// Decode a DynamoDB item into a Process
-fn decode_process<Id, ProcessorId, A>(
+/// Decode a DynamoDB item into a Process, using `codec` to decode the memoized value
+/// regardless of whether it was stored as a string (text codec) or binary blob
+/// (binary codec).
+fn decode_process<Id, ProcessorId, A, C>(
     mut attributes: HashMap<String, AttributeValue>,
+    codec: &C,
 ) -> Result<Process<Id, ProcessorId, A>, Error>
 where
     Id: DeserializeOwned,
     ProcessorId: DeserializeOwned,
     A: DeserializeOwned,
+    C: ValueCodec<A>,
 {
     let id = attributes
         .remove("id")
@@ -167,10 +499,32 @@ where
         .and_then(|v| v.as_n().ok().and_then(|s| s.parse::<i64>().ok()))
         .map(|secs| Expiration::new(UNIX_EPOCH + Duration::from_secs(secs as u64)));
 
+    let last_heartbeat_at = attributes
+        .remove("heartbeatAt")
+        .and_then(|v| v.as_n().ok().and_then(|s| s.parse::<i64>().ok()))
+        .map(|millis| UNIX_EPOCH + Duration::from_millis(millis as u64));
+
+    let failed_at = attributes
+        .remove("failedAt")
+        .and_then(|v| v.as_n().ok().and_then(|s| s.parse::<i64>().ok()))
+        .map(|millis| UNIX_EPOCH + Duration::from_millis(millis as u64));
+
+    let attempt = attributes
+        .remove("attempt")
+        .and_then(|v| v.as_n().ok().and_then(|s| s.parse::<u32>().ok()))
+        .unwrap_or(0);
+
+    let failure_reason = attributes.remove("failureReason").and_then(|v| v.as_s().ok().cloned());
+
     let memoized = attributes
         .remove("memoized")
-        .and_then(|v| v.as_s().ok().cloned())
-        .map(|s| serde_json::from_str(&s))
+        .map(|v| match v {
+            AttributeValue::S(s) => Ok(s.into_bytes()),
+            AttributeValue::B(b) => Ok(b.into_inner()),
+            _ => Err(Error::Decoding("Unexpected type for 'memoized' field".to_string())),
+        })
+        .transpose()?
+        .map(|bytes| codec.decode(&bytes))
         .transpose()?;
 
     Ok(Process {
@@ -179,6 +533,10 @@ where
         started_at,
         completed_at,
         expires_on,
+        last_heartbeat_at,
+        failed_at,
+        attempt,
+        failure_reason,
         memoized,
     })
 }
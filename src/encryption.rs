@@ -0,0 +1,268 @@
+use crate::error::Error;
+use crate::model::Process;
+use crate::persistence::Persistence;
+use aes_gcm::aead::{Aead, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use async_trait::async_trait;
+use rand::RngCore;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::marker::PhantomData;
+use std::time::{Duration, SystemTime};
+
+/// A 256-bit master key used to wrap data keys (KEK).
+///
+/// Holding several lets `EncryptingPersistence` decrypt records written under an older
+/// key while new records are wrapped under the newest one, enabling key rotation
+/// without rewriting existing data.
+#[derive(Clone)]
+pub struct MasterKey {
+    pub id: String,
+    key: [u8; 32],
+}
+
+impl MasterKey {
+    pub fn new(id: impl Into<String>, key: [u8; 32]) -> Self {
+        Self { id: id.into(), key }
+    }
+}
+
+/// Envelope-encrypted representation of a memoized value, as persisted in place of `A`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Envelope {
+    kek_id: String,
+    wrapped_dek: Vec<u8>,
+    dek_nonce: [u8; 12],
+    value_nonce: [u8; 12],
+    ciphertext: Vec<u8>,
+}
+
+/// Decorates a [`Persistence`] backend with envelope encryption of memoized values.
+///
+/// On completion, `A` is serialized to JSON, encrypted under a freshly generated
+/// 256-bit data key (DEK) with AES-256-GCM, and the DEK is itself wrapped with the
+/// newest [`MasterKey`] (KEK) using a second AES-256-GCM operation. Only the wrapped
+/// envelope ever reaches the underlying store. On read, the DEK is unwrapped with the
+/// KEK matching the envelope's `kek_id` - trying older keys lets rotation happen without
+/// rewriting already-persisted records.
+pub struct EncryptingPersistence<P, Id, ProcessorId, A> {
+    inner: P,
+    keks: Vec<MasterKey>,
+    _marker: PhantomData<(Id, ProcessorId, A)>,
+}
+
+impl<P, Id, ProcessorId, A> EncryptingPersistence<P, Id, ProcessorId, A> {
+    /// `keks` must be non-empty; the last entry is used to wrap new DEKs, and any entry
+    /// may be used to unwrap DEKs on records written under an older key.
+    pub fn new(inner: P, keks: Vec<MasterKey>) -> Self {
+        assert!(!keks.is_empty(), "EncryptingPersistence requires at least one master key");
+        Self {
+            inner,
+            keks,
+            _marker: PhantomData,
+        }
+    }
+
+    fn current_kek(&self) -> &MasterKey {
+        self.keks.last().expect("keks is non-empty")
+    }
+
+    fn find_kek(&self, kek_id: &str) -> Result<&MasterKey, Error> {
+        self.keks
+            .iter()
+            .find(|k| k.id == kek_id)
+            .ok_or_else(|| Error::Decoding(format!("unknown KEK id: {kek_id}")))
+    }
+
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Envelope, Error> {
+        let mut dek_bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut dek_bytes);
+        let dek = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&dek_bytes));
+
+        let mut value_nonce = [0u8; 12];
+        OsRng.fill_bytes(&mut value_nonce);
+        let ciphertext = dek
+            .encrypt(Nonce::from_slice(&value_nonce), plaintext)
+            .map_err(|_| Error::Encryption("failed to encrypt value".to_string()))?;
+
+        let kek = self.current_kek();
+        let kek_cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&kek.key));
+        let mut dek_nonce = [0u8; 12];
+        OsRng.fill_bytes(&mut dek_nonce);
+        let wrapped_dek = kek_cipher
+            .encrypt(Nonce::from_slice(&dek_nonce), dek_bytes.as_ref())
+            .map_err(|_| Error::Encryption("failed to wrap data key".to_string()))?;
+
+        Ok(Envelope {
+            kek_id: kek.id.clone(),
+            wrapped_dek,
+            dek_nonce,
+            value_nonce,
+            ciphertext,
+        })
+    }
+
+    fn decrypt(&self, envelope: &Envelope) -> Result<Vec<u8>, Error> {
+        let kek = self.find_kek(&envelope.kek_id)?;
+        let kek_cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&kek.key));
+        let dek_bytes = kek_cipher
+            .decrypt(Nonce::from_slice(&envelope.dek_nonce), envelope.wrapped_dek.as_ref())
+            .map_err(|_| Error::Decryption("failed to unwrap data key: tag mismatch".to_string()))?;
+
+        let dek = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&dek_bytes));
+        dek.decrypt(Nonce::from_slice(&envelope.value_nonce), envelope.ciphertext.as_ref())
+            .map_err(|_| Error::Decryption("failed to decrypt value: tag mismatch".to_string()))
+    }
+}
+
+#[async_trait]
+impl<P, Id, ProcessorId, A> Persistence<Id, ProcessorId, A> for EncryptingPersistence<P, Id, ProcessorId, A>
+where
+    P: Persistence<Id, ProcessorId, Envelope>,
+    Id: Clone + Send + Sync + 'static,
+    ProcessorId: Clone + Send + Sync + 'static,
+    A: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    async fn start_processing_update(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        now: SystemTime,
+    ) -> Result<Option<Process<Id, ProcessorId, A>>, Error> {
+        let previous = self
+            .inner
+            .start_processing_update(id, processor_id, now)
+            .await?;
+
+        previous
+            .map(|process| {
+                let memoized = process
+                    .memoized
+                    .as_ref()
+                    .map(|envelope| self.decrypt(envelope))
+                    .transpose()?
+                    .map(|bytes| serde_json::from_slice(&bytes))
+                    .transpose()?;
+
+                Ok(Process {
+                    id: process.id,
+                    processor_id: process.processor_id,
+                    started_at: process.started_at,
+                    completed_at: process.completed_at,
+                    expires_on: process.expires_on,
+                    last_heartbeat_at: process.last_heartbeat_at,
+                    failed_at: process.failed_at,
+                    attempt: process.attempt,
+                    failure_reason: process.failure_reason,
+                    memoized,
+                })
+            })
+            .transpose()
+    }
+
+    async fn reclaim_process(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        now: SystemTime,
+        expected_claim_token: SystemTime,
+    ) -> Result<(), Error> {
+        self.inner
+            .reclaim_process(id, processor_id, now, expected_claim_token)
+            .await
+    }
+
+    async fn complete_process(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        now: SystemTime,
+        ttl: Option<Duration>,
+        value: A,
+        claim_token: Option<SystemTime>,
+    ) -> Result<(), Error> {
+        let plaintext = serde_json::to_vec(&value)?;
+        let envelope = self.encrypt(&plaintext)?;
+        self.inner
+            .complete_process(id, processor_id, now, ttl, envelope, claim_token)
+            .await
+    }
+
+    async fn invalidate_process(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        claim_token: Option<SystemTime>,
+    ) -> Result<(), Error> {
+        self.inner.invalidate_process(id, processor_id, claim_token).await
+    }
+
+    async fn fail_process(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        now: SystemTime,
+        attempt: u32,
+        reason: String,
+        claim_token: Option<SystemTime>,
+    ) -> Result<(), Error> {
+        self.inner
+            .fail_process(id, processor_id, now, attempt, reason, claim_token)
+            .await
+    }
+
+    async fn heartbeat(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        now: SystemTime,
+        claim_token: Option<SystemTime>,
+    ) -> Result<(), Error> {
+        self.inner.heartbeat(id, processor_id, now, claim_token).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_kek(id: &str, seed: u8) -> MasterKey {
+        MasterKey::new(id, [seed; 32])
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_roundtrip() {
+        let inner_keks = vec![test_kek("kek-1", 1)];
+        let persistence: EncryptingPersistence<(), (), (), String> =
+            EncryptingPersistence::new((), inner_keks);
+
+        let envelope = persistence.encrypt(b"secret value").unwrap();
+        let plaintext = persistence.decrypt(&envelope).unwrap();
+        assert_eq!(plaintext, b"secret value");
+    }
+
+    #[test]
+    fn test_decrypt_with_rotated_key_still_works() {
+        let old_kek = test_kek("kek-1", 1);
+        let new_kek = test_kek("kek-2", 2);
+
+        let writer: EncryptingPersistence<(), (), (), String> =
+            EncryptingPersistence::new((), vec![old_kek.clone()]);
+        let envelope = writer.encrypt(b"secret value").unwrap();
+
+        let reader: EncryptingPersistence<(), (), (), String> =
+            EncryptingPersistence::new((), vec![old_kek, new_kek]);
+        let plaintext = reader.decrypt(&envelope).unwrap();
+        assert_eq!(plaintext, b"secret value");
+    }
+
+    #[test]
+    fn test_decrypt_with_unknown_kek_fails() {
+        let writer: EncryptingPersistence<(), (), (), String> =
+            EncryptingPersistence::new((), vec![test_kek("kek-1", 1)]);
+        let envelope = writer.encrypt(b"secret value").unwrap();
+
+        let reader: EncryptingPersistence<(), (), (), String> =
+            EncryptingPersistence::new((), vec![test_kek("kek-2", 2)]);
+        assert!(matches!(reader.decrypt(&envelope), Err(Error::Decoding(_))));
+    }
+}
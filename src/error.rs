@@ -1,22 +1,40 @@
 use thiserror::Error;
 
-#[derive(Error, Debug)]
+#[derive(Error, Debug, Clone, PartialEq)]
 pub enum Error {
     #[error("DynamoDB error: {0}")]
     DynamoDb(String),
 
+    #[error("Postgres error: {0}")]
+    Postgres(String),
+
     #[error("Encoding error: {0}")]
     Encoding(String),
 
     #[error("Decoding error: {0}")]
     Decoding(String),
 
+    #[error("Encryption error: {0}")]
+    Encryption(String),
+
+    #[error("Decryption error: {0}")]
+    Decryption(String),
+
     #[error("Process timeout")]
     Timeout,
 
     #[error("Process expired")]
     Expired,
 
+    #[error("Claim lost: a concurrent processor already completed or reclaimed this process")]
+    ClaimLost,
+
+    #[error("Process failed after {attempt} attempt(s): {reason}")]
+    ProcessFailed { attempt: u32, reason: String },
+
+    #[error("Mnemosyne is shutting down")]
+    ShuttingDown,
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
@@ -41,3 +59,9 @@ impl From<serde_json::Error> for Error {
         Error::Encoding(err.to_string())
     }
 }
+
+impl From<tokio_postgres::Error> for Error {
+    fn from(err: tokio_postgres::Error) -> Self {
+        Error::Postgres(err.to_string())
+    }
+}
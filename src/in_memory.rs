@@ -0,0 +1,283 @@
+use crate::error::Error;
+use crate::model::{Expiration, Process};
+use crate::persistence::Persistence;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+/// In-memory `Persistence` backend, keyed by `(id, processor_id)` exactly like
+/// `DynamoDbPersistence`/`PostgresPersistence`.
+///
+/// Honors the same conditional-write semantics as the real backends - a `claim_token`
+/// only succeeds while the stored `started_at` still matches and the record isn't
+/// already completed, otherwise it's `Error::ClaimLost` - so tests can exercise
+/// deduplication, memoization, invalidation, and single-flight coordination
+/// deterministically without standing up DynamoDB or Postgres.
+#[derive(Debug)]
+pub struct InMemoryPersistence<Id, ProcessorId, A> {
+    records: Mutex<HashMap<(Id, ProcessorId), Process<Id, ProcessorId, A>>>,
+}
+
+impl<Id, ProcessorId, A> InMemoryPersistence<Id, ProcessorId, A> {
+    pub fn new() -> Self {
+        Self {
+            records: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<Id, ProcessorId, A> Default for InMemoryPersistence<Id, ProcessorId, A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl<Id, ProcessorId, A> Persistence<Id, ProcessorId, A> for InMemoryPersistence<Id, ProcessorId, A>
+where
+    Id: Eq + Hash + Clone + Send + Sync + 'static,
+    ProcessorId: Eq + Hash + Clone + Send + Sync + 'static,
+    A: Clone + Send + Sync + 'static,
+{
+    async fn start_processing_update(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        now: SystemTime,
+    ) -> Result<Option<Process<Id, ProcessorId, A>>, Error> {
+        let mut records = self.records.lock().expect("lock poisoned");
+
+        // Mirrors DynamoDB's `SET startedAt = if_not_exists(startedAt, :value)`: a
+        // pre-existing record is returned untouched, and only a brand-new key gets a
+        // fresh `Process`.
+        match records.get(&(id.clone(), processor_id.clone())) {
+            Some(existing) => Ok(Some(existing.clone())),
+            None => {
+                let process = Process::new(id.clone(), processor_id.clone(), now);
+                records.insert((id, processor_id), process);
+                Ok(None)
+            }
+        }
+    }
+
+    async fn reclaim_process(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        now: SystemTime,
+        expected_claim_token: SystemTime,
+    ) -> Result<(), Error> {
+        let mut records = self.records.lock().expect("lock poisoned");
+        let key = (id, processor_id);
+
+        match records.get_mut(&key) {
+            Some(process) if process.started_at == expected_claim_token => {
+                process.started_at = now;
+                Ok(())
+            }
+            _ => Err(Error::ClaimLost),
+        }
+    }
+
+    async fn complete_process(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        now: SystemTime,
+        ttl: Option<Duration>,
+        value: A,
+        claim_token: Option<SystemTime>,
+    ) -> Result<(), Error> {
+        let mut records = self.records.lock().expect("lock poisoned");
+        let key = (id.clone(), processor_id.clone());
+
+        if let Some(token) = claim_token {
+            match records.get(&key) {
+                Some(process) if process.started_at == token && !process.is_completed() => {}
+                _ => return Err(Error::ClaimLost),
+            }
+        }
+
+        let process = records
+            .entry(key)
+            .or_insert_with(|| Process::new(id, processor_id, now));
+        process.completed_at = Some(now);
+        process.expires_on = ttl.map(|ttl| Expiration::new(now + ttl));
+        process.memoized = Some(value);
+
+        Ok(())
+    }
+
+    async fn fail_process(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        now: SystemTime,
+        attempt: u32,
+        reason: String,
+        claim_token: Option<SystemTime>,
+    ) -> Result<(), Error> {
+        let mut records = self.records.lock().expect("lock poisoned");
+        let key = (id.clone(), processor_id.clone());
+
+        if let Some(token) = claim_token {
+            match records.get(&key) {
+                Some(process) if process.started_at == token && !process.is_completed() => {}
+                _ => return Err(Error::ClaimLost),
+            }
+        }
+
+        let process = records
+            .entry(key)
+            .or_insert_with(|| Process::new(id, processor_id, now));
+        process.failed_at = Some(now);
+        process.attempt = attempt;
+        process.failure_reason = Some(reason);
+
+        Ok(())
+    }
+
+    async fn invalidate_process(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        claim_token: Option<SystemTime>,
+    ) -> Result<(), Error> {
+        let mut records = self.records.lock().expect("lock poisoned");
+        let key = (id, processor_id);
+
+        if let Some(token) = claim_token {
+            match records.get(&key) {
+                Some(process) if process.started_at == token => {}
+                _ => return Err(Error::ClaimLost),
+            }
+        }
+
+        records.remove(&key);
+        Ok(())
+    }
+
+    /// A missing record is left alone rather than treated as an error when no
+    /// `claim_token` is given, and reported as `Error::ClaimLost` when one is, matching
+    /// `DynamoDbPersistence::heartbeat`.
+    async fn heartbeat(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        now: SystemTime,
+        claim_token: Option<SystemTime>,
+    ) -> Result<(), Error> {
+        let mut records = self.records.lock().expect("lock poisoned");
+        match records.get_mut(&(id, processor_id)) {
+            Some(process) => {
+                if let Some(token) = claim_token {
+                    if process.started_at != token {
+                        return Err(Error::ClaimLost);
+                    }
+                }
+                process.last_heartbeat_at = Some(now);
+                Ok(())
+            }
+            None if claim_token.is_some() => Err(Error::ClaimLost),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_start_processing_update_claims_new_record() {
+        let persistence: InMemoryPersistence<&str, &str, String> = InMemoryPersistence::new();
+        let previous = persistence
+            .start_processing_update("id", "processor", SystemTime::now())
+            .await
+            .unwrap();
+        assert!(previous.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_start_processing_update_returns_existing_record() {
+        let persistence: InMemoryPersistence<&str, &str, String> = InMemoryPersistence::new();
+        let now = SystemTime::now();
+        persistence.start_processing_update("id", "processor", now).await.unwrap();
+
+        let previous = persistence
+            .start_processing_update("id", "processor", SystemTime::now())
+            .await
+            .unwrap();
+        assert_eq!(previous.unwrap().started_at, now);
+    }
+
+    #[tokio::test]
+    async fn test_complete_process_then_duplicate_sees_memoized_value() {
+        let persistence: InMemoryPersistence<&str, &str, String> = InMemoryPersistence::new();
+        let now = SystemTime::now();
+        persistence.start_processing_update("id", "processor", now).await.unwrap();
+        persistence
+            .complete_process("id", "processor", now, None, "result".to_string(), Some(now))
+            .await
+            .unwrap();
+
+        let previous = persistence
+            .start_processing_update("id", "processor", SystemTime::now())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(previous.memoized, Some("result".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_complete_process_with_stale_claim_token_fails() {
+        let persistence: InMemoryPersistence<&str, &str, String> = InMemoryPersistence::new();
+        let now = SystemTime::now();
+        persistence.start_processing_update("id", "processor", now).await.unwrap();
+
+        let stale_token = now - Duration::from_secs(10);
+        let result = persistence
+            .complete_process("id", "processor", now, None, "result".to_string(), Some(stale_token))
+            .await;
+        assert!(matches!(result, Err(Error::ClaimLost)));
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_process_removes_record() {
+        let persistence: InMemoryPersistence<&str, &str, String> = InMemoryPersistence::new();
+        let now = SystemTime::now();
+        persistence.start_processing_update("id", "processor", now).await.unwrap();
+        persistence.invalidate_process("id", "processor", None).await.unwrap();
+
+        let previous = persistence
+            .start_processing_update("id", "processor", SystemTime::now())
+            .await
+            .unwrap();
+        assert!(previous.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fail_process_is_visible_as_failed_status() {
+        let persistence: InMemoryPersistence<&str, &str, String> = InMemoryPersistence::new();
+        let now = SystemTime::now();
+        persistence.start_processing_update("id", "processor", now).await.unwrap();
+        persistence
+            .fail_process("id", "processor", now, 2, "boom".to_string(), Some(now))
+            .await
+            .unwrap();
+
+        let previous = persistence
+            .start_processing_update("id", "processor", SystemTime::now())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(previous.attempt, 2);
+        assert_eq!(previous.failure_reason, Some("boom".to_string()));
+        assert_eq!(
+            previous.status(Duration::from_secs(60), None),
+            crate::model::ProcessStatus::Failed(2)
+        );
+    }
+}
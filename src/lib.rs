@@ -50,15 +50,30 @@
 //! # }
 //! ```
 
+pub mod codec;
+pub mod content;
 pub mod dynamodb;
+pub mod encryption;
 pub mod error;
+pub mod in_memory;
 pub mod mnemosyne;
 pub mod model;
 pub mod persistence;
+pub mod postgres;
+pub mod retry;
 
 // Re-export commonly used types
+pub use codec::{CborCodec, JsonCodec, ValueCodec};
+pub use content::{ContentHasher, HmacSha256Hasher};
 pub use dynamodb::DynamoDbPersistence;
+pub use encryption::{EncryptingPersistence, Envelope, MasterKey};
 pub use error::Error;
+pub use in_memory::InMemoryPersistence;
 pub use mnemosyne::Mnemosyne;
-pub use model::{Config, Expiration, Outcome, PollStrategy, Process, ProcessStatus};
+pub use model::{
+    Config, EffectBackoff, EffectRetryPolicy, Expiration, HeartbeatConfig, MaxRetries, Outcome, PollStrategy,
+    PollWarnThresholds, Process, ProcessStatus,
+};
 pub use persistence::Persistence;
+pub use postgres::{HistoryConfig, PostgresPersistence};
+pub use retry::{RetryPolicy, RetryingPersistence};
@@ -1,9 +1,15 @@
+use crate::content::{hex_encode, ContentHasher, HmacSha256Hasher};
 use crate::error::Error;
 use crate::model::{Config, Outcome, PollStrategy, ProcessStatus};
 use crate::persistence::Persistence;
+use dashmap::DashMap;
+use serde::Serialize;
 use std::future::Future;
-use std::sync::Arc;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
 use std::time::{Duration, SystemTime};
+use tokio::sync::{broadcast, Notify};
 use tokio::time::sleep;
 
 #[cfg(feature = "tracing")]
@@ -23,15 +29,121 @@ macro_rules! warn {
     ($($tt:tt)*) => {{}};
 }
 
+// Emit counters/histograms via the `metrics` crate, mirroring the tracing no-op pattern
+// above so instrumentation compiles away entirely when the `metrics` feature is off.
+#[cfg(feature = "metrics")]
+macro_rules! record_counter {
+    ($name:expr, $value:expr $(, $label:expr => $label_value:expr)*) => {
+        metrics::counter!($name $(, $label => $label_value)*).increment($value)
+    };
+}
+#[cfg(not(feature = "metrics"))]
+macro_rules! record_counter {
+    ($($tt:tt)*) => {{}};
+}
+#[cfg(feature = "metrics")]
+macro_rules! record_histogram {
+    ($name:expr, $value:expr $(, $label:expr => $label_value:expr)*) => {
+        metrics::histogram!($name $(, $label => $label_value)*).record($value)
+    };
+}
+#[cfg(not(feature = "metrics"))]
+macro_rules! record_histogram {
+    ($($tt:tt)*) => {{}};
+}
+
+/// Removes a single-flight leader's entry from the in-flight registry on drop, covering
+/// every way a leader can stop running: it finishes, it panics, or its future is dropped
+/// because the caller cancelled it.
+struct LeaderGuard<'a, Id, A>
+where
+    Id: Eq + Hash,
+{
+    in_flight: &'a DashMap<Id, Weak<broadcast::Sender<Result<A, Error>>>>,
+    id: Id,
+}
+
+/// Aborts a spawned background [`tokio::task::JoinHandle`] on drop, so a task survives
+/// exactly as long as the future that owns this guard - including when that future is
+/// cancelled (caller drops it) rather than running to completion.
+struct AbortOnDrop(Option<tokio::task::JoinHandle<()>>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        if let Some(task) = self.0.take() {
+            task.abort();
+        }
+    }
+}
+
+/// Shared cancellation signal for [`Mnemosyne::shutdown`].
+///
+/// `notify` wakes sleeping heartbeat/poll loops as soon as shutdown is requested;
+/// `cancelled` is re-checked on every loop iteration so a task started (or a wakeup
+/// missed) just before `notify_waiters()` fires still exits at most one interval later
+/// rather than being wedged indefinitely. `active_tasks` lets `shutdown` wait for every
+/// currently running heartbeat task to actually stop instead of just firing the signal
+/// and hoping.
+struct ShutdownState {
+    cancelled: AtomicBool,
+    notify: Notify,
+    active_tasks: AtomicUsize,
+}
+
+impl ShutdownState {
+    fn new() -> Self {
+        Self {
+            cancelled: AtomicBool::new(false),
+            notify: Notify::new(),
+            active_tasks: AtomicUsize::new(0),
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// Decrements [`ShutdownState::active_tasks`] when a background task ends for any
+/// reason - normal completion, a cooperative break on shutdown, or [`AbortOnDrop`]
+/// cancelling it - so [`Mnemosyne::shutdown`] can tell when every background task has
+/// actually stopped.
+struct ActiveTaskGuard(Arc<ShutdownState>);
+
+impl Drop for ActiveTaskGuard {
+    fn drop(&mut self) {
+        self.0.active_tasks.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl<'a, Id, A> Drop for LeaderGuard<'a, Id, A>
+where
+    Id: Eq + Hash,
+{
+    fn drop(&mut self) {
+        self.in_flight.remove(&self.id);
+    }
+}
+
 /// Main Mnemosyne API for deduplicating process execution
 pub struct Mnemosyne<Id, ProcessorId, A> {
     persistence: Arc<dyn Persistence<Id, ProcessorId, A>>,
     config: Config<ProcessorId>,
+    // In-process single-flight registry: while `config.single_flight` is enabled, the
+    // first local caller for an `Id` becomes the leader and the rest subscribe here
+    // instead of independently hitting the backend. Entries are removed once the leader
+    // resolves, so a later call re-drives the claim from scratch. Holding only a `Weak`
+    // means the map never keeps the channel open by itself: if the leader's own `Arc`
+    // drops (it finished, panicked, or was cancelled) before a prospective waiter
+    // upgrades, that waiter sees the leader is already gone and takes over instead of
+    // subscribing to a channel nothing will ever send on.
+    in_flight: DashMap<Id, Weak<broadcast::Sender<Result<A, Error>>>>,
+    shutdown: Arc<ShutdownState>,
 }
 
 impl<Id, ProcessorId, A> Mnemosyne<Id, ProcessorId, A>
 where
-    Id: Clone + Send + Sync + std::fmt::Debug + 'static,
+    Id: Clone + Eq + Hash + Send + Sync + std::fmt::Debug + 'static,
     ProcessorId: Clone + Send + Sync + std::fmt::Debug + 'static,
     A: Clone + Send + Sync + 'static,
 {
@@ -43,6 +155,28 @@ where
         Self {
             persistence,
             config,
+            in_flight: DashMap::new(),
+            shutdown: Arc::new(ShutdownState::new()),
+        }
+    }
+
+    /// Signal every in-flight heartbeat task to stop and wait for them to actually do so,
+    /// then make subsequent `protect`/`try_start_process`/`protect_batch` calls fail fast
+    /// with `Error::ShuttingDown` instead of spawning new background work.
+    ///
+    /// Call this before dropping a `Mnemosyne` whose runtime is tearing down, rather than
+    /// relying on `Drop` timing: a heartbeat task left running on a dying executor is the
+    /// exact class of teardown panic connection-pool background tasks are prone to.
+    #[cfg_attr(feature = "tracing", instrument(skip(self)))]
+    pub async fn shutdown(&self) {
+        self.shutdown.cancelled.store(true, Ordering::SeqCst);
+        self.shutdown.notify.notify_waiters();
+
+        // A plain poll rather than a condition variable: heartbeat tasks only observe
+        // `cancelled` once per loop iteration anyway, so there's no instantaneous signal
+        // to block on, and polling sidesteps any lost-wakeup race entirely.
+        while self.shutdown.active_tasks.load(Ordering::SeqCst) > 0 {
+            sleep(Duration::from_millis(10)).await;
         }
     }
 
@@ -52,9 +186,21 @@ where
     /// - `Outcome::New` with a completion callback if this is the first processor
     /// - `Outcome::Duplicate` with memoized value if already processed
     ///
-    /// This provides the low-level API for manual control. Most users should use `once()` instead.
-    #[cfg_attr(feature = "tracing", instrument(skip(self), fields(signal_id = ?id)))]
+    /// This provides the low-level API for manual control. Most users should use `protect()` instead.
+    #[cfg_attr(feature = "tracing", instrument(skip(self), fields(signal_id = ?id, processor_id = ?self.config.processor_id)))]
     pub async fn try_start_process(&self, id: Id) -> Result<Outcome<A>, Error> {
+        self.try_start_process_with_token(id).await.map(|(outcome, _)| outcome)
+    }
+
+    /// Same as [`Self::try_start_process`], but also returns the `claim_token` (the
+    /// `started_at` backing the claim) for callers - namely [`Self::protect_direct`] and
+    /// [`Self::protect_batch`] - that need it to fence a spawned heartbeat task against a
+    /// claim this instance no longer owns.
+    async fn try_start_process_with_token(&self, id: Id) -> Result<(Outcome<A>, SystemTime), Error> {
+        if self.shutdown.is_cancelled() {
+            return Err(Error::ShuttingDown);
+        }
+
         let now = SystemTime::now();
         let processor_id = self.config.processor_id.clone();
         let max_processing_time = self.config.max_processing_time;
@@ -71,26 +217,48 @@ where
             None => {
                 // This is a new process
                 info!("New process - no previous record found");
-                Ok(self.create_new_outcome(id, processor_id))
+                record_counter!("mnemosyne_outcomes_total", 1, "outcome" => "new");
+                Ok((self.create_new_outcome(id, processor_id, now), now))
             }
             Some(process) => {
                 // A record exists - determine its status
-                let status = process.status(max_processing_time);
+                let status = process.status(max_processing_time, self.config.heartbeat.map(|h| h.grace_period));
+                let claim_token = process.started_at;
 
                 match status {
                     ProcessStatus::Completed(memoized) => {
                         info!("Process already completed - returning memoized value");
-                        Ok(Outcome::Duplicate {
-                            value: memoized.clone(),
-                        })
+                        record_counter!("mnemosyne_outcomes_total", 1, "outcome" => "duplicate");
+                        Ok((
+                            Outcome::Duplicate {
+                                value: memoized.clone(),
+                            },
+                            claim_token,
+                        ))
                     }
                     ProcessStatus::Expired => {
                         info!("Previous process expired - allowing retry");
-                        Ok(self.create_new_outcome(id, processor_id))
+                        record_counter!("mnemosyne_outcomes_total", 1, "outcome" => "expired");
+                        self.reclaim(id, processor_id, claim_token).await
                     }
                     ProcessStatus::Timeout => {
                         info!("Previous process timed out - allowing retry");
-                        Ok(self.create_new_outcome(id, processor_id))
+                        record_counter!("mnemosyne_outcomes_total", 1, "outcome" => "timeout");
+                        self.reclaim(id, processor_id, claim_token).await
+                    }
+                    ProcessStatus::Failed(attempt) => {
+                        if self.config.retry_policy.is_some_and(|policy| policy.allows_attempt(attempt)) {
+                            info!(attempt, "Previous attempt failed - allowing retry");
+                            record_counter!("mnemosyne_outcomes_total", 1, "outcome" => "failed_retry");
+                            self.reclaim(id, processor_id, claim_token).await
+                        } else {
+                            warn!(attempt, "Previous attempt failed - retries exhausted");
+                            record_counter!("mnemosyne_outcomes_total", 1, "outcome" => "failed");
+                            Err(Error::ProcessFailed {
+                                attempt,
+                                reason: process.failure_reason.clone().unwrap_or_default(),
+                            })
+                        }
                     }
                     ProcessStatus::Running => {
                         // Process is still running - poll and wait
@@ -101,7 +269,8 @@ where
                     ProcessStatus::NotStarted => {
                         // Shouldn't happen since we have a process record
                         info!("Unexpected NotStarted status - treating as new");
-                        Ok(self.create_new_outcome(id, processor_id))
+                        record_counter!("mnemosyne_outcomes_total", 1, "outcome" => "new");
+                        self.reclaim(id, processor_id, claim_token).await
                     }
                 }
             }
@@ -112,17 +281,258 @@ where
     ///
     /// Provides at-least-once semantics with best-effort exactly-once through
     /// distributed deduplication. Returns the result whether from fresh execution
-    /// or memoized from a previous run.
+    /// or memoized from a previous run. `f` is called at most once - even if
+    /// `Config::retry_policy` is set, this method never retries the effect itself (the
+    /// claim is simply left in place for `max_processing_time`, as before); use
+    /// [`Self::protect_retrying`] for a repeatable `f` that should be retried in-process.
     #[cfg_attr(feature = "tracing", instrument(skip(self, f), fields(signal_id = ?id)))]
-    pub async fn once<F, Fut>(&self, id: Id, f: F) -> Result<A, Error>
+    pub async fn protect<F, Fut>(&self, id: Id, f: F) -> Result<A, Error>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<A, Error>>,
+    {
+        let start_time = SystemTime::now();
+        let result = self.protect_inner(id, f).await;
+
+        if let Ok(elapsed) = SystemTime::now().duration_since(start_time) {
+            record_histogram!("mnemosyne_protect_duration_seconds", elapsed.as_secs_f64());
+        }
+
+        result
+    }
+
+    /// Like [`Self::protect`], but derives the idempotency `Id` from `payload` itself
+    /// instead of requiring the caller to mint one - "same input => same key => executed
+    /// once" for things like webhook bodies, without callers threading UUIDs through
+    /// their own code. Uses [`HmacSha256Hasher`] keyed by [`Config::content_hash_key`];
+    /// see [`Self::protect_content_with_hasher`] to swap in a different algorithm.
+    pub async fn protect_content<P, F, Fut>(&self, payload: &P, f: F) -> Result<A, Error>
+    where
+        P: Serialize,
+        Id: From<String>,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<A, Error>>,
+    {
+        self.protect_content_with_hasher(&HmacSha256Hasher, payload, f).await
+    }
+
+    /// Same as [`Self::protect_content`], with the [`ContentHasher`] passed explicitly.
+    pub async fn protect_content_with_hasher<H, P, F, Fut>(
+        &self,
+        hasher: &H,
+        payload: &P,
+        f: F,
+    ) -> Result<A, Error>
+    where
+        H: ContentHasher,
+        P: Serialize,
+        Id: From<String>,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<A, Error>>,
+    {
+        let id = self.content_id(hasher, payload)?;
+        self.protect(id, f).await
+    }
+
+    /// Like [`Self::protect`], but retries a failing `f` with backoff according to
+    /// `Config::retry_policy` before giving up, instead of propagating the first error.
+    /// Since `f` may be invoked more than once, it must be repeatable (`Fn`, not
+    /// `FnOnce`) - callers that close over owned state should clone it inside the
+    /// closure body rather than moving it in.
+    ///
+    /// With no `retry_policy` configured, this behaves exactly like [`Self::protect`]:
+    /// `f` runs once and its error propagates immediately.
+    #[cfg_attr(feature = "tracing", instrument(skip(self, f), fields(signal_id = ?id)))]
+    pub async fn protect_retrying<F, Fut>(&self, id: Id, f: F) -> Result<A, Error>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<A, Error>>,
+    {
+        let start_time = SystemTime::now();
+        let result = self.protect_retrying_inner(id, f).await;
+
+        if let Ok(elapsed) = SystemTime::now().duration_since(start_time) {
+            record_histogram!("mnemosyne_protect_duration_seconds", elapsed.as_secs_f64());
+        }
+
+        result
+    }
+
+    /// Canonicalizes `payload` to JSON and runs it through `hasher` keyed by
+    /// `Config::content_hash_key`, rendering the digest as a hex `Id`.
+    fn content_id<H, P>(&self, hasher: &H, payload: &P) -> Result<Id, Error>
+    where
+        H: ContentHasher,
+        P: Serialize,
+        Id: From<String>,
+    {
+        let key = self.config.content_hash_key.as_deref().unwrap_or(&[]);
+        let canonical = serde_json::to_vec(payload)?;
+        let digest = hasher.digest(key, &canonical);
+        Ok(Id::from(hex_encode(&digest)))
+    }
+
+    async fn protect_inner<F, Fut>(&self, id: Id, f: F) -> Result<A, Error>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<A, Error>>,
+    {
+        if !self.config.single_flight {
+            return self.protect_direct(id, f).await;
+        }
+
+        // Subscribe to an already in-flight leader rather than touching the backend.
+        // `subscribe_to_leader` upgrades the map's `Weak<Sender>` just long enough to
+        // call `subscribe`, so this caller only ever holds a `Receiver` afterward.
+        if let Some(receiver) = self.subscribe_to_leader(&id) {
+            record_counter!("mnemosyne_single_flight_total", 1, "role" => "follower");
+            return self.await_leader(receiver).await;
+        }
+
+        // Race to become the leader: only the caller that successfully inserts the
+        // vacant entry (or replaces a stale entry whose leader's `Arc` is already gone)
+        // drives the backend claim; everyone else falls back to waiting.
+        let sender = Arc::new(broadcast::channel(1).0);
+        match self.in_flight.entry(id.clone()) {
+            dashmap::mapref::entry::Entry::Vacant(vacant) => {
+                vacant.insert(Arc::downgrade(&sender));
+                record_counter!("mnemosyne_single_flight_total", 1, "role" => "leader");
+            }
+            dashmap::mapref::entry::Entry::Occupied(mut occupied) => {
+                if let Some(existing) = occupied.get().upgrade() {
+                    record_counter!("mnemosyne_single_flight_total", 1, "role" => "follower");
+                    let receiver = existing.subscribe();
+                    drop(existing);
+                    return self.await_leader(receiver).await;
+                }
+                // The previous leader's `Arc` is already gone (it finished, panicked, or
+                // was cancelled) but its `LeaderGuard` hasn't removed the entry yet -
+                // take over as the new leader rather than subscribing to a dead channel.
+                occupied.insert(Arc::downgrade(&sender));
+                record_counter!("mnemosyne_single_flight_total", 1, "role" => "leader");
+            }
+        };
+
+        // The guard removes the map entry on drop, whether `protect_direct` returns
+        // normally, panics, or is cancelled by the caller dropping this future - so a
+        // crashed or cancelled leader can never leave behind a stale entry pointing at a
+        // sender nobody will ever send on, which would otherwise wedge every waiter that
+        // subscribes to it.
+        let _guard = LeaderGuard {
+            in_flight: &self.in_flight,
+            id: id.clone(),
+        };
+
+        let result = self.protect_direct(id.clone(), f).await;
+        // Ignore send errors: they just mean every waiter already gave up. Errors are
+        // broadcast like any other result but, since the entry is removed right after,
+        // they are never cached - the next caller re-drives the claim from scratch.
+        let _ = sender.send(result.clone());
+        result
+    }
+
+    /// Same single-flight dispatch as [`Self::protect_inner`], but for
+    /// [`Self::protect_retrying`] where `f` is `Fn` and may be driven by
+    /// [`Self::protect_retrying_direct`] more than once.
+    async fn protect_retrying_inner<F, Fut>(&self, id: Id, f: F) -> Result<A, Error>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<A, Error>>,
+    {
+        if !self.config.single_flight {
+            return self.protect_retrying_direct(id, f).await;
+        }
+
+        // Subscribe to an already in-flight leader rather than touching the backend.
+        // `subscribe_to_leader` upgrades the map's `Weak<Sender>` just long enough to
+        // call `subscribe`, so this caller only ever holds a `Receiver` afterward.
+        if let Some(receiver) = self.subscribe_to_leader(&id) {
+            record_counter!("mnemosyne_single_flight_total", 1, "role" => "follower");
+            return self.await_leader(receiver).await;
+        }
+
+        // Race to become the leader: only the caller that successfully inserts the
+        // vacant entry (or replaces a stale entry whose leader's `Arc` is already gone)
+        // drives the backend claim; everyone else falls back to waiting.
+        let sender = Arc::new(broadcast::channel(1).0);
+        match self.in_flight.entry(id.clone()) {
+            dashmap::mapref::entry::Entry::Vacant(vacant) => {
+                vacant.insert(Arc::downgrade(&sender));
+                record_counter!("mnemosyne_single_flight_total", 1, "role" => "leader");
+            }
+            dashmap::mapref::entry::Entry::Occupied(mut occupied) => {
+                if let Some(existing) = occupied.get().upgrade() {
+                    record_counter!("mnemosyne_single_flight_total", 1, "role" => "follower");
+                    let receiver = existing.subscribe();
+                    drop(existing);
+                    return self.await_leader(receiver).await;
+                }
+                // The previous leader's `Arc` is already gone (it finished, panicked, or
+                // was cancelled) but its `LeaderGuard` hasn't removed the entry yet -
+                // take over as the new leader rather than subscribing to a dead channel.
+                occupied.insert(Arc::downgrade(&sender));
+                record_counter!("mnemosyne_single_flight_total", 1, "role" => "leader");
+            }
+        };
+
+        let _guard = LeaderGuard {
+            in_flight: &self.in_flight,
+            id: id.clone(),
+        };
+
+        let result = self.protect_retrying_direct(id.clone(), f).await;
+        let _ = sender.send(result.clone());
+        result
+    }
+
+    /// Look up an in-flight leader for `id` and subscribe to it, if its `Arc<Sender>` is
+    /// still alive.
+    ///
+    /// Upgrading the map's `Weak` just long enough to call `subscribe`, then dropping
+    /// the upgraded `Arc` before returning, means the caller is left holding only a
+    /// [`broadcast::Receiver`] - never a strong reference that could itself keep the
+    /// channel open.
+    fn subscribe_to_leader(&self, id: &Id) -> Option<broadcast::Receiver<Result<A, Error>>> {
+        let weak = self.in_flight.get(id)?.clone();
+        let sender = weak.upgrade()?;
+        Some(sender.subscribe())
+    }
+
+    /// Wait for the leader of an in-flight single-flight group to resolve.
+    ///
+    /// Takes ownership of only a [`broadcast::Receiver`], never a `Sender` - so this
+    /// waiter can never itself keep the channel open. If the leader panics or its future
+    /// is cancelled before it calls `send`, every `Sender`/`Weak<Sender>` reference to
+    /// this channel is dropped once it (and the map's [`LeaderGuard`]) unwind, the
+    /// channel closes, and `recv` resolves to the retryable error below instead of
+    /// blocking forever.
+    async fn await_leader(&self, mut receiver: broadcast::Receiver<Result<A, Error>>) -> Result<A, Error> {
+        receiver
+            .recv()
+            .await
+            .unwrap_or_else(|_| Err(Error::Internal("single-flight leader was dropped before completing".to_string())))
+    }
+
+    /// The pre-single-flight behavior: always drives the backend claim directly.
+    /// Calls `f` exactly once - a failure propagates immediately and the claim is left
+    /// in place for `max_processing_time`, regardless of `Config::retry_policy`; see
+    /// [`Self::protect_retrying_direct`] for the retry-capable counterpart.
+    async fn protect_direct<F, Fut>(&self, id: Id, f: F) -> Result<A, Error>
     where
         F: FnOnce() -> Fut,
         Fut: Future<Output = Result<A, Error>>,
     {
-        let outcome = self.try_start_process(id).await?;
+        let processor_id = self.config.processor_id.clone();
+        let (outcome, claim_token) = self.try_start_process_with_token(id.clone()).await?;
 
         match outcome {
             Outcome::New { complete_process } => {
+                // The guard aborts the heartbeat task on every exit path below - normal
+                // return or the caller cancelling this future - so a slow effect can never
+                // leave a heartbeat running against a claim nobody owns anymore.
+                let _heartbeat_guard =
+                    AbortOnDrop(self.spawn_heartbeat(id.clone(), processor_id.clone(), claim_token));
+
                 let result = f().await?;
                 complete_process(result.clone()).await?;
                 Ok(result)
@@ -131,15 +541,276 @@ where
         }
     }
 
+    /// Same as [`Self::protect_direct`], but for [`Self::protect_retrying`]: retries a
+    /// failing `f` with backoff according to `Config::retry_policy` before giving up,
+    /// calling `f` as many times as the policy allows since it's bound `Fn` rather than
+    /// `FnOnce`.
+    async fn protect_retrying_direct<F, Fut>(&self, id: Id, f: F) -> Result<A, Error>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<A, Error>>,
+    {
+        let processor_id = self.config.processor_id.clone();
+        let (outcome, claim_token) = self.try_start_process_with_token(id.clone()).await?;
+
+        match outcome {
+            Outcome::New { complete_process } => {
+                // The guard aborts the heartbeat task on every exit path below - normal
+                // return or the caller cancelling this future - so a slow effect can never
+                // leave a heartbeat running against a claim nobody owns anymore.
+                let _heartbeat_guard =
+                    AbortOnDrop(self.spawn_heartbeat(id.clone(), processor_id.clone(), claim_token));
+
+                let result = async {
+                    let Some(retry_policy) = self.config.retry_policy else {
+                        let result = f().await?;
+                        complete_process(result.clone()).await?;
+                        return Ok(result);
+                    };
+
+                    let mut attempt = 0;
+                    loop {
+                        match f().await {
+                            Ok(result) => {
+                                complete_process(result.clone()).await?;
+                                return Ok(result);
+                            }
+                            Err(err) if retry_policy.allows_attempt(attempt) => {
+                                warn!(attempt, ?err, "Effect failed - retrying after backoff");
+                                sleep(retry_policy.delay_for_attempt(attempt)).await;
+                                attempt += 1;
+                            }
+                            Err(err) => {
+                                // Retries exhausted: persist the failure (so a later
+                                // claimant - possibly a different processor - can see how
+                                // many attempts were made) instead of just releasing the
+                                // claim, then surface the terminal error.
+                                self.persistence
+                                    .fail_process(
+                                        id.clone(),
+                                        processor_id.clone(),
+                                        SystemTime::now(),
+                                        attempt + 1,
+                                        err.to_string(),
+                                        Some(claim_token),
+                                    )
+                                    .await?;
+                                return Err(err);
+                            }
+                        }
+                    }
+                }
+                .await;
+
+                result
+            }
+            Outcome::Duplicate { value } => Ok(value),
+        }
+    }
+
+    /// Spawn a background task that periodically calls [`Persistence::heartbeat`] while
+    /// an effect for `id` is running, returning `None` when `Config::heartbeat` isn't
+    /// set. Callers must abort the returned task once the claim is completed or released
+    /// so it doesn't keep heartbeating (and, on backends without a no-op-on-missing-row
+    /// guard, potentially resurrecting) a record nobody owns anymore.
+    ///
+    /// `claim_token` fences every renewal to the `started_at` this instance observed
+    /// when it claimed the process: if another processor reclaims the record (this one
+    /// having fallen behind `max_processing_time`), the backend reports `Error::ClaimLost`
+    /// and the loop stops rather than resurrecting liveness for a claim it no longer holds.
+    fn spawn_heartbeat(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        claim_token: SystemTime,
+    ) -> Option<tokio::task::JoinHandle<()>> {
+        let heartbeat_config = self.config.heartbeat?;
+        let persistence = Arc::clone(&self.persistence);
+        let shutdown = Arc::clone(&self.shutdown);
+        shutdown.active_tasks.fetch_add(1, Ordering::SeqCst);
+
+        Some(tokio::spawn(async move {
+            // Dropped when this task ends for any reason, including `AbortOnDrop`
+            // cancelling it, so `Mnemosyne::shutdown` always sees an accurate count.
+            let _guard = ActiveTaskGuard(Arc::clone(&shutdown));
+
+            while !shutdown.is_cancelled() {
+                tokio::select! {
+                    _ = sleep(heartbeat_config.interval) => {}
+                    _ = shutdown.notify.notified() => break,
+                }
+                if shutdown.is_cancelled() {
+                    break;
+                }
+                match persistence
+                    .heartbeat(id.clone(), processor_id.clone(), SystemTime::now(), Some(claim_token))
+                    .await
+                {
+                    Ok(()) => {}
+                    Err(Error::ClaimLost) => {
+                        warn!("Heartbeat lost its claim - another processor took over, stopping");
+                        break;
+                    }
+                    Err(err) => warn!(?err, "Heartbeat failed"),
+                }
+            }
+        }))
+    }
+
     /// Invalidate a previously processed signal
     #[cfg_attr(feature = "tracing", instrument(skip(self), fields(signal_id = ?id)))]
     pub async fn invalidate(&self, id: Id) -> Result<(), Error> {
         let processor_id = self.config.processor_id.clone();
-        self.persistence.invalidate_process(id, processor_id).await
+        self.persistence.invalidate_process(id, processor_id, None).await
+    }
+
+    /// Attempt to start processing a batch of signals in one call.
+    ///
+    /// Claims every id in one call to [`Persistence::start_processing_batch`] (which
+    /// backends like DynamoDB can fulfil with a single `TransactWriteItems`/
+    /// `BatchWriteItem` round-trip, transparently split into multiple requests if the
+    /// batch exceeds the backend's transaction size limit), then resolves each id to an
+    /// `Outcome` the same way [`Self::try_start_process`] does for one, polling in place
+    /// for ids a concurrent processor is still running. Returns each id paired with its
+    /// `Outcome`, in the order ids were given.
+    ///
+    /// This provides the low-level API for manual control over a batch. Most users
+    /// should use [`Self::protect_batch`] instead.
+    #[cfg_attr(feature = "tracing", instrument(skip(self)))]
+    pub async fn try_start_batch(&self, ids: Vec<Id>) -> Result<Vec<(Id, Result<Outcome<A>, Error>)>, Error> {
+        self.try_start_batch_with_token(ids)
+            .await
+            .map(|results| {
+                results
+                    .into_iter()
+                    .map(|(id, result)| (id, result.map(|(outcome, _)| outcome)))
+                    .collect()
+            })
+    }
+
+    /// Same as [`Self::try_start_batch`], but also returns each id's `claim_token` -
+    /// needed by [`Self::protect_batch`] to fence its spawned heartbeat tasks the same
+    /// way [`Self::try_start_process_with_token`] does for a single id.
+    async fn try_start_batch_with_token(
+        &self,
+        ids: Vec<Id>,
+    ) -> Result<Vec<(Id, Result<(Outcome<A>, SystemTime), Error>)>, Error> {
+        if self.shutdown.is_cancelled() {
+            return Err(Error::ShuttingDown);
+        }
+
+        let now = SystemTime::now();
+        let processor_id = self.config.processor_id.clone();
+        let max_processing_time = self.config.max_processing_time;
+
+        let claims = self
+            .persistence
+            .start_processing_batch(ids, processor_id.clone(), now)
+            .await?;
+
+        let mut results = Vec::with_capacity(claims.len());
+
+        for (id, previous) in claims {
+            let outcome = match previous {
+                None => Ok((self.create_new_outcome(id.clone(), processor_id.clone(), now), now)),
+                Some(process) => match process.status(max_processing_time, self.config.heartbeat.map(|h| h.grace_period))
+                {
+                    ProcessStatus::Completed(memoized) => Ok((
+                        Outcome::Duplicate {
+                            value: memoized.clone(),
+                        },
+                        process.started_at,
+                    )),
+                    ProcessStatus::Expired | ProcessStatus::Timeout | ProcessStatus::NotStarted => {
+                        self.reclaim(id.clone(), processor_id.clone(), process.started_at).await
+                    }
+                    ProcessStatus::Failed(attempt) => {
+                        if self.config.retry_policy.is_some_and(|policy| policy.allows_attempt(attempt)) {
+                            self.reclaim(id.clone(), processor_id.clone(), process.started_at).await
+                        } else {
+                            Err(Error::ProcessFailed {
+                                attempt,
+                                reason: process.failure_reason.clone().unwrap_or_default(),
+                            })
+                        }
+                    }
+                    ProcessStatus::Running => {
+                        self.poll_for_completion(id.clone(), processor_id.clone(), max_processing_time)
+                            .await
+                    }
+                },
+            };
+
+            results.push((id, outcome));
+        }
+
+        Ok(results)
     }
 
-    /// Create a New outcome with the completion callback
-    fn create_new_outcome(&self, id: Id, processor_id: ProcessorId) -> Outcome<A> {
+    /// Run an effect once per id across a batch of signals.
+    ///
+    /// Built on [`Self::try_start_batch`]: drives `f` for every id that needs fresh
+    /// execution, spawning the same per-id heartbeat as [`Self::protect`] while it runs,
+    /// and skips straight to the memoized value for ids already completed elsewhere.
+    /// Returns each id paired with its result, in the order ids were given, so callers
+    /// can tell which ids in the batch succeeded versus which failed; a fresh result and
+    /// a memoized one are both plain `Ok` values.
+    #[cfg_attr(feature = "tracing", instrument(skip(self, f)))]
+    pub async fn protect_batch<F, Fut>(&self, ids: Vec<Id>, f: F) -> Result<Vec<(Id, Result<A, Error>)>, Error>
+    where
+        F: Fn(Id) -> Fut,
+        Fut: Future<Output = Result<A, Error>>,
+    {
+        let processor_id = self.config.processor_id.clone();
+        let claims = self.try_start_batch_with_token(ids).await?;
+
+        let mut results = Vec::with_capacity(claims.len());
+
+        for (id, outcome) in claims {
+            let result = match outcome {
+                Err(err) => Err(err),
+                Ok((Outcome::New { complete_process }, claim_token)) => {
+                    let _heartbeat_guard =
+                        AbortOnDrop(self.spawn_heartbeat(id.clone(), processor_id.clone(), claim_token));
+                    match f(id.clone()).await {
+                        Ok(value) => complete_process(value.clone()).await.map(|_| value),
+                        Err(err) => Err(err),
+                    }
+                }
+                Ok((Outcome::Duplicate { value }, _)) => Ok(value),
+            };
+
+            results.push((id, result));
+        }
+
+        Ok(results)
+    }
+
+    /// Reclaim a stale, timed-out, or failed-but-retriable claim via
+    /// [`Persistence::reclaim_process`], stamping a fresh `started_at` so this reclaim
+    /// gets its own fencing token rather than inheriting `previous_claim_token` - the
+    /// value the processor it's reclaiming from still carries, which would otherwise let
+    /// the two race to complete with an identical, indistinguishable token.
+    async fn reclaim(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        previous_claim_token: SystemTime,
+    ) -> Result<(Outcome<A>, SystemTime), Error> {
+        let now = SystemTime::now();
+        self.persistence
+            .reclaim_process(id.clone(), processor_id.clone(), now, previous_claim_token)
+            .await?;
+        Ok((self.create_new_outcome(id, processor_id, now), now))
+    }
+
+    /// Create a New outcome with the completion callback.
+    ///
+    /// `claim_token` is the `started_at` this caller observed when claiming the process
+    /// (or `now` for a brand-new record), passed through to [`Persistence::complete_process`]
+    /// as a fencing guard so a processor that falls behind can't clobber a result a newer
+    /// processor already wrote.
+    fn create_new_outcome(&self, id: Id, processor_id: ProcessorId, claim_token: SystemTime) -> Outcome<A> {
         let persistence = Arc::clone(&self.persistence);
         let ttl = self.config.ttl;
 
@@ -147,9 +818,13 @@ where
             complete_process: Box::new(move |value: A| {
                 Box::pin(async move {
                     let now = SystemTime::now();
-                    persistence
-                        .complete_process(id, processor_id, now, ttl, value)
-                        .await
+                    let result = persistence
+                        .complete_process(id, processor_id, now, ttl, value, Some(claim_token))
+                        .await;
+                    if result.is_ok() {
+                        info!(?ttl, "Process completed");
+                    }
+                    result
                 })
             }),
         }
@@ -162,14 +837,18 @@ where
         id: Id,
         processor_id: ProcessorId,
         max_processing_time: Duration,
-    ) -> Result<Outcome<A>, Error> {
+    ) -> Result<(Outcome<A>, SystemTime), Error> {
         let poll_strategy = self.config.poll_strategy;
         let max_poll_duration = poll_strategy.max_duration();
         let start_time = SystemTime::now();
 
         let mut attempt = 0;
+        // Tracks the `started_at` of the last process record we observed, so the
+        // max-poll-duration branch below has a fencing token even though it has no
+        // `process` in scope for that iteration.
+        let mut last_seen_started_at: Option<SystemTime> = None;
 
-        loop {
+        let outcome = loop {
             // Calculate delay based on strategy
             let delay = match poll_strategy {
                 PollStrategy::Linear { delay, .. } => delay,
@@ -183,7 +862,13 @@ where
                 }
             };
 
-            sleep(delay).await;
+            if self.shutdown.is_cancelled() {
+                return Err(Error::ShuttingDown);
+            }
+            tokio::select! {
+                _ = sleep(delay) => {}
+                _ = self.shutdown.notify.notified() => return Err(Error::ShuttingDown),
+            }
             attempt += 1;
 
             // Check if we've exceeded max poll duration
@@ -191,9 +876,26 @@ where
                 .duration_since(start_time)
                 .map_err(|e| Error::Internal(e.to_string()))?;
 
+            // Borrowed from pict-rs's `WithPollTimer`: surface a signal wedged behind a
+            // slow or dead processor well before `max_poll_duration` finally gives up.
+            if let Some(thresholds) = self.config.poll_warn_thresholds {
+                if delay >= thresholds.single_wait {
+                    warn!(?delay, "Single poll wait exceeded warning threshold");
+                }
+                if elapsed >= thresholds.cumulative {
+                    warn!(?elapsed, "Cumulative poll time exceeded warning threshold");
+                }
+            }
+
             if elapsed >= max_poll_duration {
                 warn!("Polling exceeded max duration - treating as timeout");
-                return Ok(self.create_new_outcome(id, processor_id));
+                break match last_seen_started_at {
+                    Some(previous_claim_token) => self.reclaim(id, processor_id, previous_claim_token).await?,
+                    None => {
+                        let claim_token = SystemTime::now();
+                        (self.create_new_outcome(id, processor_id, claim_token), claim_token)
+                    }
+                };
             }
 
             // Try to start again - will check current status
@@ -204,18 +906,34 @@ where
                 .await?;
 
             if let Some(process) = previous_process {
-                let status = process.status(max_processing_time);
+                last_seen_started_at = Some(process.started_at);
+                let status = process.status(max_processing_time, self.config.heartbeat.map(|h| h.grace_period));
 
                 match status {
                     ProcessStatus::Completed(memoized) => {
                         info!("Process completed during polling");
-                        return Ok(Outcome::Duplicate {
-                            value: memoized.clone(),
-                        });
+                        break (
+                            Outcome::Duplicate {
+                                value: memoized.clone(),
+                            },
+                            process.started_at,
+                        );
                     }
                     ProcessStatus::Expired | ProcessStatus::Timeout => {
                         info!("Process expired/timed out during polling");
-                        return Ok(self.create_new_outcome(id, processor_id));
+                        break self.reclaim(id, processor_id, process.started_at).await?;
+                    }
+                    ProcessStatus::Failed(attempt) => {
+                        if self.config.retry_policy.is_some_and(|policy| policy.allows_attempt(attempt)) {
+                            info!(attempt, "Previous attempt failed during polling - allowing retry");
+                            break self.reclaim(id, processor_id, process.started_at).await?;
+                        } else {
+                            warn!(attempt, "Previous attempt failed during polling - retries exhausted");
+                            return Err(Error::ProcessFailed {
+                                attempt,
+                                reason: process.failure_reason.clone().unwrap_or_default(),
+                            });
+                        }
                     }
                     ProcessStatus::Running => {
                         // Still running, continue polling
@@ -224,13 +942,177 @@ where
                     }
                     ProcessStatus::NotStarted => {
                         // Process disappeared or never existed
-                        return Ok(self.create_new_outcome(id, processor_id));
+                        break self.reclaim(id, processor_id, process.started_at).await?;
                     }
                 }
             } else {
                 // Process record disappeared
-                return Ok(self.create_new_outcome(id, processor_id));
+                let claim_token = SystemTime::now();
+                break (self.create_new_outcome(id, processor_id, claim_token), claim_token);
             }
+        };
+
+        record_counter!("mnemosyne_poll_attempts_total", attempt as u64);
+        if let Ok(elapsed) = SystemTime::now().duration_since(start_time) {
+            record_histogram!("mnemosyne_poll_duration_seconds", elapsed.as_secs_f64());
         }
+
+        Ok(outcome)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::in_memory::InMemoryPersistence;
+
+    #[tokio::test]
+    async fn test_single_flight_coalesces_concurrent_callers_into_one_effect_run() {
+        let persistence = Arc::new(InMemoryPersistence::<&str, &str, String>::new());
+        let config = Config::new(
+            "processor",
+            Duration::from_secs(60),
+            None,
+            PollStrategy::linear(Duration::from_millis(5), Duration::from_secs(5)),
+        )
+        .with_single_flight(true);
+        let mnemosyne: Arc<Mnemosyne<&str, &str, String>> = Arc::new(Mnemosyne::new(persistence, config));
+
+        let effect_runs = Arc::new(AtomicUsize::new(0));
+
+        let callers = (0..20).map(|_| {
+            let mnemosyne = Arc::clone(&mnemosyne);
+            let effect_runs = Arc::clone(&effect_runs);
+            tokio::spawn(async move {
+                mnemosyne
+                    .protect("signal", || {
+                        let effect_runs = Arc::clone(&effect_runs);
+                        async move {
+                            effect_runs.fetch_add(1, Ordering::SeqCst);
+                            sleep(Duration::from_millis(20)).await;
+                            Ok("result".to_string())
+                        }
+                    })
+                    .await
+            })
+        });
+
+        let results: Vec<Result<String, Error>> = futures::future::join_all(callers)
+            .await
+            .into_iter()
+            .map(|joined| joined.expect("task panicked"))
+            .collect();
+
+        for result in results {
+            assert_eq!(result.unwrap(), "result");
+        }
+        assert_eq!(effect_runs.load(Ordering::SeqCst), 1);
+    }
+
+    #[derive(Serialize)]
+    struct Payload {
+        recipient: &'static str,
+        subject: &'static str,
+    }
+
+    #[tokio::test]
+    async fn test_protect_content_dedupes_identical_payloads() {
+        let persistence = Arc::new(InMemoryPersistence::<String, &str, String>::new());
+        let config = Config::new(
+            "processor",
+            Duration::from_secs(60),
+            None,
+            PollStrategy::linear(Duration::from_millis(5), Duration::from_secs(5)),
+        );
+        let mnemosyne: Mnemosyne<String, &str, String> = Mnemosyne::new(persistence, config);
+
+        let payload = Payload {
+            recipient: "a@example.com",
+            subject: "hello",
+        };
+
+        let first = mnemosyne
+            .protect_content(&payload, || async { Ok("sent".to_string()) })
+            .await
+            .unwrap();
+        let second = mnemosyne
+            .protect_content(&payload, || async { Ok("sent-again".to_string()) })
+            .await
+            .unwrap();
+
+        assert_eq!(first, "sent");
+        assert_eq!(second, "sent", "identical payload must be deduped, not re-run");
+    }
+
+    #[tokio::test]
+    async fn test_protect_content_differs_by_payload() {
+        let persistence = Arc::new(InMemoryPersistence::<String, &str, String>::new());
+        let config = Config::new(
+            "processor",
+            Duration::from_secs(60),
+            None,
+            PollStrategy::linear(Duration::from_millis(5), Duration::from_secs(5)),
+        );
+        let mnemosyne: Mnemosyne<String, &str, String> = Mnemosyne::new(persistence, config);
+
+        let first = mnemosyne
+            .protect_content(
+                &Payload {
+                    recipient: "a@example.com",
+                    subject: "hello",
+                },
+                || async { Ok("sent-a".to_string()) },
+            )
+            .await
+            .unwrap();
+        let second = mnemosyne
+            .protect_content(
+                &Payload {
+                    recipient: "b@example.com",
+                    subject: "hello",
+                },
+                || async { Ok("sent-b".to_string()) },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(first, "sent-a");
+        assert_eq!(second, "sent-b");
+    }
+
+    #[tokio::test]
+    async fn test_protect_batch_runs_each_distinct_id_once_and_dedupes_repeats() {
+        let persistence = Arc::new(InMemoryPersistence::<&str, &str, String>::new());
+        let config = Config::new(
+            "processor",
+            Duration::from_secs(60),
+            None,
+            PollStrategy::linear(Duration::from_millis(5), Duration::from_secs(5)),
+        );
+        let mnemosyne: Mnemosyne<&str, &str, String> = Mnemosyne::new(persistence, config);
+
+        let first = mnemosyne
+            .protect_batch(vec!["signal-a", "signal-b"], |id| async move { Ok(format!("{id}-result")) })
+            .await
+            .unwrap();
+        assert_eq!(
+            first,
+            vec![
+                ("signal-a", Ok("signal-a-result".to_string())),
+                ("signal-b", Ok("signal-b-result".to_string())),
+            ]
+        );
+
+        let second = mnemosyne
+            .protect_batch(vec!["signal-b", "signal-c"], |id| async move { Ok(format!("{id}-rerun")) })
+            .await
+            .unwrap();
+        assert_eq!(
+            second,
+            vec![
+                ("signal-b", Ok("signal-b-result".to_string())),
+                ("signal-c", Ok("signal-c-rerun".to_string())),
+            ]
+        );
     }
 }
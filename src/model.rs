@@ -40,6 +40,13 @@ pub enum ProcessStatus<A> {
     Timeout,
     /// Process record expired (TTL exceeded)
     Expired,
+    /// The effect returned `Err` and the failure was persisted via
+    /// [`Persistence::fail_process`](crate::Persistence::fail_process), carrying the
+    /// number of attempts made so far. Distinct from `Timeout`/`Expired`: those describe
+    /// a record that can simply be reclaimed, while a caller still has to decide (by
+    /// comparing this attempt count against its own retry policy) whether to retry or
+    /// surface the failure as terminal.
+    Failed(u32),
 }
 
 /// TTL expiration timestamp
@@ -77,6 +84,22 @@ pub struct Process<Id, ProcessorId, A> {
     pub completed_at: Option<SystemTime>,
     /// When record expires (TTL)
     pub expires_on: Option<Expiration>,
+    /// Last time a processor reported liveness for this process, if heartbeating is in
+    /// use (see [`crate::model::HeartbeatConfig`]). `None` for backends or records that
+    /// predate heartbeat support.
+    pub last_heartbeat_at: Option<SystemTime>,
+    /// When the most recent attempt failed, if the effect has ever returned `Err` and
+    /// that failure was persisted (see [`crate::Persistence::fail_process`]). Cleared
+    /// implicitly once `completed_at` is set, since a later successful attempt always
+    /// takes priority in [`Self::status`].
+    pub failed_at: Option<SystemTime>,
+    /// Number of attempts that have failed so far. `0` until the first persisted
+    /// failure.
+    pub attempt: u32,
+    /// `Display` of the error from the most recent failed attempt, kept so a caller
+    /// whose retries are exhausted gets a meaningful terminal error instead of a bare
+    /// "process failed".
+    pub failure_reason: Option<String>,
     /// Memoized result value
     pub memoized: Option<A>,
 }
@@ -89,6 +112,10 @@ impl<Id, ProcessorId, A> Process<Id, ProcessorId, A> {
             started_at,
             completed_at: None,
             expires_on: None,
+            last_heartbeat_at: None,
+            failed_at: None,
+            attempt: 0,
+            failure_reason: None,
             memoized: None,
         }
     }
@@ -97,21 +124,41 @@ impl<Id, ProcessorId, A> Process<Id, ProcessorId, A> {
         self.completed_at.is_some()
     }
 
+    /// Whether the most recent attempt failed and no later attempt has completed since.
+    pub fn is_failed(&self) -> bool {
+        !self.is_completed() && self.failed_at.is_some()
+    }
+
     pub fn is_expired(&self) -> bool {
         self.expires_on.is_some_and(|e| e.is_expired())
     }
 
-    pub fn is_timeout(&self, max_processing_time: Duration) -> bool {
+    /// Whether this process should be considered timed out (and so reclaimable).
+    ///
+    /// When `heartbeat_grace_period` is set, liveness is judged by how long ago
+    /// `last_heartbeat_at` (falling back to `started_at` if no heartbeat has landed yet)
+    /// was updated, rather than by the fixed `max_processing_time` window. This lets a
+    /// worker keep a genuinely long effect alive by heartbeating, while a crashed worker
+    /// that stops heartbeating is reclaimed after the much shorter grace period instead
+    /// of waiting out the whole `max_processing_time`.
+    pub fn is_timeout(&self, max_processing_time: Duration, heartbeat_grace_period: Option<Duration>) -> bool {
         if self.is_completed() {
             return false;
         }
 
+        if let Some(grace_period) = heartbeat_grace_period {
+            let last_seen = self.last_heartbeat_at.unwrap_or(self.started_at);
+            return SystemTime::now()
+                .duration_since(last_seen)
+                .is_ok_and(|elapsed| elapsed >= grace_period);
+        }
+
         SystemTime::now()
             .duration_since(self.started_at)
             .is_ok_and(|elapsed| elapsed >= max_processing_time)
     }
 
-    pub fn status(&self, max_processing_time: Duration) -> ProcessStatus<&A>
+    pub fn status(&self, max_processing_time: Duration, heartbeat_grace_period: Option<Duration>) -> ProcessStatus<&A>
     where
         A: Clone,
     {
@@ -121,11 +168,15 @@ impl<Id, ProcessorId, A> Process<Id, ProcessorId, A> {
             }
         }
 
+        if self.is_failed() {
+            return ProcessStatus::Failed(self.attempt);
+        }
+
         if self.is_expired() {
             return ProcessStatus::Expired;
         }
 
-        if self.is_timeout(max_processing_time) {
+        if self.is_timeout(max_processing_time, heartbeat_grace_period) {
             return ProcessStatus::Timeout;
         }
 
@@ -173,6 +224,124 @@ impl PollStrategy {
     }
 }
 
+/// How many times a failed effect may be retried before
+/// [`Mnemosyne::protect_retrying`](crate::Mnemosyne::protect_retrying) gives up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaxRetries {
+    /// Keep retrying until the effect succeeds.
+    Infinite,
+    /// Give up after this many additional attempts beyond the first.
+    Count(u32),
+}
+
+/// Backoff shape between retries of a failed effect, reusing [`PollStrategy`]'s linear
+/// vs. exponential split rather than inventing a third shape.
+#[derive(Debug, Clone, Copy)]
+pub enum EffectBackoff {
+    /// Retry immediately.
+    None,
+    /// Wait a fixed delay between every attempt.
+    Linear { delay: Duration },
+    /// Wait `base * multiplier^attempt` between attempts.
+    Exponential { base: Duration, multiplier: f64 },
+}
+
+/// Retry policy applied to the user effect passed to
+/// [`Mnemosyne::protect_retrying`](crate::Mnemosyne::protect_retrying).
+///
+/// Unlike [`crate::RetryPolicy`] (which retries transient *backend* errors underneath a
+/// [`Persistence`](crate::Persistence) call), this retries the caller's own effect when it
+/// returns `Err`, so a flaky downstream dependency doesn't leave the id stuck until
+/// `max_processing_time` elapses.
+#[derive(Debug, Clone, Copy)]
+pub struct EffectRetryPolicy {
+    pub max_retries: MaxRetries,
+    pub backoff: EffectBackoff,
+    /// Scale each computed delay by a random factor in `[0, 1]` to avoid synchronized
+    /// retries across processes racing the same backend.
+    pub jitter: bool,
+}
+
+impl EffectRetryPolicy {
+    pub fn new(max_retries: MaxRetries, backoff: EffectBackoff, jitter: bool) -> Self {
+        Self {
+            max_retries,
+            backoff,
+            jitter,
+        }
+    }
+
+    pub(crate) fn allows_attempt(&self, attempt: u32) -> bool {
+        match self.max_retries {
+            MaxRetries::Infinite => true,
+            MaxRetries::Count(max) => attempt < max,
+        }
+    }
+
+    pub(crate) fn delay_for_attempt(&self, attempt: u32) -> Duration {
+        let base_delay = match self.backoff {
+            EffectBackoff::None => Duration::ZERO,
+            EffectBackoff::Linear { delay } => delay,
+            EffectBackoff::Exponential { base, multiplier } => {
+                Duration::from_secs_f64(base.as_secs_f64() * multiplier.powi(attempt as i32))
+            }
+        };
+
+        if self.jitter {
+            let factor: f64 = rand::random::<f64>();
+            Duration::from_secs_f64(base_delay.as_secs_f64() * factor)
+        } else {
+            base_delay
+        }
+    }
+}
+
+/// Heartbeat/lease-renewal settings for [`Mnemosyne::protect`](crate::Mnemosyne::protect).
+///
+/// While a process is running, a background task calls [`Persistence::heartbeat`]
+/// (crate::Persistence::heartbeat) every `interval` to refresh `last_heartbeat_at`; a
+/// process is reclaimed as [`ProcessStatus::Timeout`] once that timestamp is older than
+/// `grace_period`, independent of the overall `max_processing_time`.
+#[derive(Debug, Clone, Copy)]
+pub struct HeartbeatConfig {
+    /// How long a process may go without a heartbeat before it's considered dead.
+    pub grace_period: Duration,
+    /// How often the background task refreshes the heartbeat. Should be comfortably
+    /// shorter than `grace_period` so a couple of missed beats don't cause a false
+    /// timeout.
+    pub interval: Duration,
+}
+
+impl HeartbeatConfig {
+    pub fn new(grace_period: Duration, interval: Duration) -> Self {
+        Self {
+            grace_period,
+            interval,
+        }
+    }
+}
+
+/// Escalating `warn!` thresholds for [`Mnemosyne::poll_for_completion`](crate::Mnemosyne),
+/// borrowed from pict-rs's `WithPollTimer`.
+///
+/// A signal polled behind a slow or dead processor is otherwise silent until
+/// `max_poll_duration` is finally exceeded; these thresholds surface it earlier so
+/// operators aren't left guessing why a caller is still waiting.
+#[derive(Debug, Clone, Copy)]
+pub struct PollWarnThresholds {
+    /// Log a warning if a single poll wait (the `sleep` between two claim attempts)
+    /// takes longer than this.
+    pub single_wait: Duration,
+    /// Log a warning if the cumulative time spent polling a signal exceeds this.
+    pub cumulative: Duration,
+}
+
+impl PollWarnThresholds {
+    pub fn new(single_wait: Duration, cumulative: Duration) -> Self {
+        Self { single_wait, cumulative }
+    }
+}
+
 /// Configuration for Mnemosyne
 #[derive(Debug, Clone)]
 pub struct Config<ProcessorId> {
@@ -184,6 +353,29 @@ pub struct Config<ProcessorId> {
     pub ttl: Option<Duration>,
     /// How to poll for in-progress processes
     pub poll_strategy: PollStrategy,
+    /// Whether concurrent local callers for the same `Id` should be coalesced into a
+    /// single backend claim (see [`crate::Mnemosyne::protect`]). Disabled by default so
+    /// the existing direct-to-backend behavior is preserved unless opted into.
+    pub single_flight: bool,
+    /// Retry policy applied to the user effect on failure by
+    /// [`Mnemosyne::protect_retrying`](crate::Mnemosyne::protect_retrying). `None` (the
+    /// default) preserves today's behavior: a failed effect's error propagates
+    /// immediately and the claim is left in place until `max_processing_time` elapses.
+    /// Ignored by [`Mnemosyne::protect`](crate::Mnemosyne::protect), which only ever
+    /// calls the effect once.
+    pub retry_policy: Option<EffectRetryPolicy>,
+    /// Heartbeat/lease-renewal settings. `None` (the default) preserves today's
+    /// behavior: liveness is judged solely by `max_processing_time`.
+    pub heartbeat: Option<HeartbeatConfig>,
+    /// Escalating `warn!` thresholds for long-running polls. `None` (the default)
+    /// preserves today's behavior: nothing is logged until `max_poll_duration` is
+    /// exceeded and polling gives up.
+    pub poll_warn_thresholds: Option<PollWarnThresholds>,
+    /// HMAC key for [`crate::Mnemosyne::protect_content`]'s derived idempotency ids.
+    /// `None` (the default) uses an empty key, which is fine for a single-tenant
+    /// deployment but lets anyone who can guess a payload also guess its id - set this
+    /// per tenant/deployment to prevent that.
+    pub content_hash_key: Option<Vec<u8>>,
 }
 
 impl<ProcessorId> Config<ProcessorId> {
@@ -198,8 +390,51 @@ impl<ProcessorId> Config<ProcessorId> {
             max_processing_time,
             ttl,
             poll_strategy,
+            single_flight: false,
+            retry_policy: None,
+            heartbeat: None,
+            poll_warn_thresholds: None,
+            content_hash_key: None,
         }
     }
+
+    /// Enable in-process single-flight coalescing of concurrent `protect()` calls
+    /// sharing the same `Id`.
+    pub fn with_single_flight(mut self, enabled: bool) -> Self {
+        self.single_flight = enabled;
+        self
+    }
+
+    /// Retry the user effect on failure according to `policy` instead of propagating
+    /// the first error, when driven through
+    /// [`Mnemosyne::protect_retrying`](crate::Mnemosyne::protect_retrying).
+    pub fn with_retry_policy(mut self, policy: EffectRetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Detect a dead processor by liveness (a lapsed heartbeat) rather than solely by
+    /// `max_processing_time`, so a crashed worker is reclaimed quickly while a genuinely
+    /// long effect stays protected as long as it keeps beating.
+    pub fn with_heartbeat(mut self, heartbeat: HeartbeatConfig) -> Self {
+        self.heartbeat = Some(heartbeat);
+        self
+    }
+
+    /// Log escalating warnings for a signal stuck polling behind another processor,
+    /// instead of staying silent until `max_poll_duration` gives up.
+    pub fn with_poll_warn_thresholds(mut self, thresholds: PollWarnThresholds) -> Self {
+        self.poll_warn_thresholds = Some(thresholds);
+        self
+    }
+
+    /// Key [`crate::Mnemosyne::protect_content`]'s derived ids with `key`, so different
+    /// tenants/deployments sharing this library can't collide on (or guess) each other's
+    /// content-addressed ids.
+    pub fn with_content_hash_key(mut self, key: impl Into<Vec<u8>>) -> Self {
+        self.content_hash_key = Some(key.into());
+        self
+    }
 }
 
 #[cfg(test)]
@@ -269,7 +504,7 @@ mod tests {
             Process::new("id", "processor", SystemTime::now());
         process.completed_at = Some(SystemTime::now());
         // Completed processes should never timeout
-        assert!(!process.is_timeout(Duration::from_secs(0)));
+        assert!(!process.is_timeout(Duration::from_secs(0), None));
     }
 
     #[test]
@@ -277,7 +512,7 @@ mod tests {
         let process: Process<&str, &str, String> =
             Process::new("id", "processor", SystemTime::now());
         let max_processing_time = Duration::from_secs(10);
-        assert!(!process.is_timeout(max_processing_time));
+        assert!(!process.is_timeout(max_processing_time, None));
     }
 
     #[test]
@@ -285,14 +520,14 @@ mod tests {
         let past_time = SystemTime::now() - Duration::from_secs(20);
         let process: Process<&str, &str, String> = Process::new("id", "processor", past_time);
         let max_processing_time = Duration::from_secs(10);
-        assert!(process.is_timeout(max_processing_time));
+        assert!(process.is_timeout(max_processing_time, None));
     }
 
     #[test]
     fn test_process_status_running() {
         let process: Process<&str, &str, String> =
             Process::new("id", "processor", SystemTime::now());
-        let status = process.status(Duration::from_secs(60));
+        let status = process.status(Duration::from_secs(60), None);
         assert_eq!(status, ProcessStatus::Running);
     }
 
@@ -302,7 +537,7 @@ mod tests {
         process.completed_at = Some(SystemTime::now());
         process.memoized = Some("result".to_string());
 
-        let status = process.status(Duration::from_secs(60));
+        let status = process.status(Duration::from_secs(60), None);
         match status {
             ProcessStatus::Completed(value) => assert_eq!(*value, "result"),
             _ => panic!("Expected Completed status"),
@@ -316,7 +551,7 @@ mod tests {
         let past_time = SystemTime::now() - Duration::from_secs(10);
         process.expires_on = Some(Expiration::new(past_time));
 
-        let status = process.status(Duration::from_secs(60));
+        let status = process.status(Duration::from_secs(60), None);
         assert_eq!(status, ProcessStatus::Expired);
     }
 
@@ -325,7 +560,7 @@ mod tests {
         let past_time = SystemTime::now() - Duration::from_secs(20);
         let process: Process<&str, &str, String> = Process::new("id", "processor", past_time);
 
-        let status = process.status(Duration::from_secs(10));
+        let status = process.status(Duration::from_secs(10), None);
         assert_eq!(status, ProcessStatus::Timeout);
     }
 
@@ -345,7 +580,7 @@ mod tests {
         process.expires_on = Some(Expiration::new(past_expiration));
 
         // Should return Expired (higher priority than Timeout)
-        let status = process.status(Duration::from_secs(10));
+        let status = process.status(Duration::from_secs(10), None);
         assert_eq!(status, ProcessStatus::Expired);
     }
 
@@ -360,7 +595,7 @@ mod tests {
         let past_time = SystemTime::now() - Duration::from_secs(10);
         process.expires_on = Some(Expiration::new(past_time));
 
-        let status = process.status(Duration::from_secs(60));
+        let status = process.status(Duration::from_secs(60), None);
         match status {
             ProcessStatus::Completed(value) => assert_eq!(*value, "result"),
             _ => panic!("Expected Completed status to override Expired"),
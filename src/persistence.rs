@@ -15,7 +15,42 @@ pub trait Persistence<Id, ProcessorId, A>: Send + Sync {
         now: SystemTime,
     ) -> Result<Option<Process<Id, ProcessorId, A>>, Error>;
 
-    /// Mark a process as completed with a memoized result
+    /// Reclaim a stale, timed-out, or failed-but-retriable claim, stamping a fresh
+    /// `started_at` so this reclaim's fencing token can never collide with the claim it
+    /// replaces.
+    ///
+    /// `expected_claim_token` is the `started_at` the caller observed when it decided
+    /// the previous claim was reclaimable (via [`ProcessStatus`](crate::model::ProcessStatus)).
+    /// A conforming backend only overwrites `started_at` with `now` if the stored value
+    /// still equals `expected_claim_token`, returning `Error::ClaimLost` if another
+    /// processor already reclaimed it first - this is what makes `now` a meaningful
+    /// fencing token for the reclaiming processor's subsequent [`Self::complete_process`],
+    /// [`Self::fail_process`] and [`Self::heartbeat`] calls, instead of every reclaimer
+    /// of the same stale claim sharing the one timestamp the original owner set.
+    ///
+    /// The default implementation is a no-op, so backends that don't implement this
+    /// mirror their old behavior: `started_at` never changes on reclaim, and distinct
+    /// reclaimers of the same claim are indistinguishable to fencing.
+    async fn reclaim_process(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        now: SystemTime,
+        expected_claim_token: SystemTime,
+    ) -> Result<(), Error> {
+        let _ = (id, processor_id, now, expected_claim_token);
+        Ok(())
+    }
+
+    /// Mark a process as completed with a memoized result.
+    ///
+    /// `claim_token` is an optional fencing guard, typically the `started_at` a caller
+    /// observed when it claimed the process. When `Some`, a conforming backend only
+    /// writes the completion if the stored `started_at` still equals it and the record
+    /// isn't already completed, returning `Error::ClaimLost` otherwise - this stops a
+    /// processor that fell behind (e.g. past `max_processing_time`) from clobbering a
+    /// result a newer processor already wrote. `None` preserves the unconditional
+    /// overwrite backends have always done, for direct callers that don't need fencing.
     async fn complete_process(
         &self,
         id: Id,
@@ -23,8 +58,103 @@ pub trait Persistence<Id, ProcessorId, A>: Send + Sync {
         now: SystemTime,
         ttl: Option<Duration>,
         value: A,
+        claim_token: Option<SystemTime>,
     ) -> Result<(), Error>;
 
-    /// Delete a process record
-    async fn invalidate_process(&self, id: Id, processor_id: ProcessorId) -> Result<(), Error>;
+    /// Delete a process record, optionally guarded by the same fencing `claim_token` as
+    /// [`Self::complete_process`] so a caller only invalidates the claim it actually
+    /// holds. `None` deletes unconditionally.
+    async fn invalidate_process(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        claim_token: Option<SystemTime>,
+    ) -> Result<(), Error>;
+
+    /// Mark a process's most recent attempt as failed, persisting `attempt` and `reason`
+    /// so a later `start_processing_update` can see `ProcessStatus::Failed` and decide
+    /// whether to retry or surface the failure as terminal - unlike
+    /// [`Self::invalidate_process`], which erases the record entirely and loses the
+    /// attempt count across crashes/reclaims.
+    ///
+    /// `claim_token` is the same fencing guard as [`Self::complete_process`]. The default
+    /// implementation falls back to [`Self::invalidate_process`], so backends that don't
+    /// implement persisted failure tracking keep today's behavior: the claim is simply
+    /// released and attempts aren't tracked.
+    async fn fail_process(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        now: SystemTime,
+        attempt: u32,
+        reason: String,
+        claim_token: Option<SystemTime>,
+    ) -> Result<(), Error> {
+        let _ = (now, attempt, reason);
+        self.invalidate_process(id, processor_id, claim_token).await
+    }
+
+    /// Refresh `last_heartbeat_at` for a running process, used for liveness-based
+    /// timeout detection (see [`crate::model::HeartbeatConfig`]).
+    ///
+    /// `claim_token` is the same fencing guard as [`Self::complete_process`]: when `Some`,
+    /// a conforming backend only renews the heartbeat while the stored `started_at` still
+    /// equals it, returning `Error::ClaimLost` otherwise. This stops a heartbeat task left
+    /// running past its claim (e.g. another processor reclaimed the record after this one
+    /// fell behind `max_processing_time`) from resurrecting liveness for a process it no
+    /// longer owns. `None` keeps the unconditional renewal behavior.
+    ///
+    /// The default implementation is a no-op, so backends that don't implement
+    /// heartbeating keep working with `max_processing_time`-only timeout detection;
+    /// backends that do support it should override this and make the update a no-op
+    /// (not an error) if the record no longer exists, since the process may have already
+    /// been invalidated.
+    async fn heartbeat(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        now: SystemTime,
+        claim_token: Option<SystemTime>,
+    ) -> Result<(), Error> {
+        let _ = (id, processor_id, now, claim_token);
+        Ok(())
+    }
+
+    /// Attempt to start processing a batch of signals.
+    ///
+    /// Returns the prior record (if any) for each id, in the same order as `ids`. The
+    /// default implementation simply loops over [`Self::start_processing_update`], so
+    /// every backend gets a working (if not batched) implementation for free; backends
+    /// that support a genuine batched/transactional write should override this.
+    async fn start_processing_batch(
+        &self,
+        ids: Vec<Id>,
+        processor_id: ProcessorId,
+        now: SystemTime,
+    ) -> Result<Vec<(Id, Option<Process<Id, ProcessorId, A>>)>, Error>
+    where
+        Id: Clone,
+        ProcessorId: Clone,
+    {
+        let mut results = Vec::with_capacity(ids.len());
+        for id in ids {
+            let previous = self
+                .start_processing_update(id.clone(), processor_id.clone(), now)
+                .await?;
+            results.push((id, previous));
+        }
+        Ok(results)
+    }
+
+    /// Mark a batch of processes as completed. The default implementation loops over
+    /// [`Self::complete_process`], unconditionally (no per-item fencing token).
+    async fn complete_process_batch(
+        &self,
+        items: Vec<(Id, ProcessorId, SystemTime, Option<Duration>, A)>,
+    ) -> Result<(), Error> {
+        for (id, processor_id, now, ttl, value) in items {
+            self.complete_process(id, processor_id, now, ttl, value, None).await?;
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,619 @@
+use crate::error::Error;
+use crate::model::{Expiration, Process};
+use crate::persistence::Persistence;
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Serialize};
+use std::marker::PhantomData;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio_postgres::Client;
+
+#[cfg(feature = "tracing")]
+use tracing::instrument;
+
+/// Optional retention settings for the append-only history table.
+///
+/// When set, every `complete_process`/`invalidate_process` call, after updating/removing
+/// the live row, also appends a row to `<table_name>_history` recording the completion
+/// (with its memoized value) or the invalidation, so completed or invalidated processes
+/// remain auditable after they are pruned from the live table.
+#[derive(Debug, Clone, Copy)]
+pub struct HistoryConfig {
+    /// How long a history snapshot is kept before it is eligible for pruning.
+    pub history_time_to_live: Duration,
+    /// Maximum number of snapshots retained per `(id, processor_id)` pair.
+    pub max_snapshot_count: u32,
+}
+
+/// Postgres-backed persistence implementation.
+///
+/// Mirrors `DynamoDbPersistence`'s conditional-write semantics on top of `tokio-postgres`:
+/// `start_processing_update` uses `INSERT ... ON CONFLICT (id, processor_id) DO UPDATE ...
+/// RETURNING` to atomically claim a row and hand back the prior record, and an `expires_on`
+/// column replaces DynamoDB's native TTL so a periodic `DELETE WHERE expires_on < now()`
+/// reaper can keep the table small.
+pub struct PostgresPersistence<Id, ProcessorId, A> {
+    client: Client,
+    table_name: String,
+    history: Option<HistoryConfig>,
+    _marker: PhantomData<(Id, ProcessorId, A)>,
+}
+
+impl<Id, ProcessorId, A> PostgresPersistence<Id, ProcessorId, A> {
+    pub fn new(client: Client, table_name: String) -> Self {
+        Self {
+            client,
+            table_name,
+            history: None,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Enable the append-only history table for this backend.
+    pub fn with_history(mut self, history: HistoryConfig) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    fn history_table_name(&self) -> String {
+        format!("{}_history", self.table_name)
+    }
+
+    /// DDL for the live table plus, when history is enabled, the history table.
+    ///
+    /// Exposed so callers can run it themselves (e.g. via `sqlx migrate` or a startup
+    /// hook) rather than mnemosyne reaching into migration tooling it doesn't own.
+    pub fn schema(&self) -> String {
+        let mut ddl = format!(
+            "CREATE TABLE IF NOT EXISTS {table} (
+                id TEXT NOT NULL,
+                processor_id TEXT NOT NULL,
+                started_at BIGINT NOT NULL,
+                completed_at BIGINT,
+                expires_on BIGINT,
+                heartbeat_at BIGINT,
+                failed_at BIGINT,
+                attempt INTEGER NOT NULL DEFAULT 0,
+                failure_reason TEXT,
+                memoized TEXT,
+                PRIMARY KEY (id, processor_id)
+            );
+            CREATE INDEX IF NOT EXISTS {table}_expires_on_idx ON {table} (expires_on);
+            CREATE INDEX IF NOT EXISTS {table}_started_at_idx ON {table} (started_at) WHERE completed_at IS NULL;",
+            table = self.table_name,
+        );
+
+        if self.history.is_some() {
+            ddl.push_str(&format!(
+                "
+            CREATE TABLE IF NOT EXISTS {history_table} (
+                id TEXT NOT NULL,
+                processor_id TEXT NOT NULL,
+                started_at BIGINT NOT NULL,
+                completed_at BIGINT,
+                memoized TEXT,
+                recorded_at BIGINT NOT NULL,
+                invalidated BOOLEAN NOT NULL DEFAULT FALSE
+            );
+            CREATE INDEX IF NOT EXISTS {history_table}_recorded_at_idx ON {history_table} (recorded_at);",
+                history_table = self.history_table_name(),
+            ));
+        }
+
+        ddl
+    }
+
+    async fn record_history(
+        &self,
+        id_str: &str,
+        processor_id_str: &str,
+        started_at_millis: i64,
+        completed_at_millis: Option<i64>,
+        memoized_str: Option<&str>,
+        invalidated: bool,
+    ) -> Result<(), Error> {
+        let Some(history) = self.history else {
+            return Ok(());
+        };
+
+        let recorded_at = now_millis()?;
+
+        self.client
+            .execute(
+                &format!(
+                    "INSERT INTO {history_table}
+                        (id, processor_id, started_at, completed_at, memoized, recorded_at, invalidated)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7)",
+                    history_table = self.history_table_name(),
+                ),
+                &[
+                    &id_str,
+                    &processor_id_str,
+                    &started_at_millis,
+                    &completed_at_millis,
+                    &memoized_str,
+                    &recorded_at,
+                    &invalidated,
+                ],
+            )
+            .await
+            .map_err(|e| Error::Postgres(e.to_string()))?;
+
+        self.client
+            .execute(
+                &format!(
+                    "DELETE FROM {history_table}
+                     WHERE id = $1 AND processor_id = $2
+                       AND (recorded_at < $3 OR ctid NOT IN (
+                            SELECT ctid FROM {history_table}
+                            WHERE id = $1 AND processor_id = $2
+                            ORDER BY recorded_at DESC
+                            LIMIT $4
+                       ))",
+                    history_table = self.history_table_name(),
+                ),
+                &[
+                    &id_str,
+                    &processor_id_str,
+                    &(recorded_at - history.history_time_to_live.as_millis() as i64),
+                    &(history.max_snapshot_count as i64),
+                ],
+            )
+            .await
+            .map_err(|e| Error::Postgres(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Delete every row whose `expires_on` has passed, using the `{table}_expires_on_idx`
+    /// index created by [`Self::schema`]. Postgres has no native per-item TTL like
+    /// DynamoDB's, so callers should run this periodically (e.g. from a cron task or a
+    /// `tokio::time::interval` loop) to keep the live table from growing unbounded.
+    /// Returns the number of rows deleted.
+    ///
+    /// This is a maintenance helper on the `tokio-postgres`-backed [`PostgresPersistence`]
+    /// introduced in [`Self::new`], not a second Postgres backend - this crate
+    /// deliberately has exactly one Postgres `Persistence` implementation rather than a
+    /// competing `sqlx`/`diesel-async` one with its own `INSERT ... ON CONFLICT DO
+    /// NOTHING RETURNING` claim semantics, since the existing `INSERT ... ON CONFLICT DO
+    /// UPDATE ... RETURNING` in [`Self::start_processing_update`] already claims and
+    /// returns the prior record atomically in one round trip - a strict superset of what
+    /// `DO NOTHING RETURNING` alone would give a caller.
+    #[cfg_attr(feature = "tracing", instrument(skip(self)))]
+    pub async fn reap_expired(&self) -> Result<u64, Error> {
+        let now = now_millis()?;
+
+        let rows_affected = self
+            .client
+            .execute(
+                &format!(
+                    "DELETE FROM {table} WHERE expires_on IS NOT NULL AND expires_on < $1",
+                    table = self.table_name,
+                ),
+                &[&now],
+            )
+            .await
+            .map_err(|e| Error::Postgres(e.to_string()))?;
+
+        Ok(rows_affected)
+    }
+
+    /// List the `(id, processor_id)` of every unfinished record whose liveness
+    /// (`heartbeat_at`, falling back to `started_at`) is older than `max_processing_time`,
+    /// using the `{table}_started_at_idx` partial index created by [`Self::schema`].
+    ///
+    /// This is a read-only monitoring helper for operators who want to alert on or
+    /// inspect stuck processes - actual reclaiming already happens lock-free the next
+    /// time a caller claims the id, via the same conditional-write fencing
+    /// [`Self::complete_process`]/[`Self::fail_process`]/[`Self::invalidate_process`] use
+    /// elsewhere, so there's no contending writer here for `SELECT ... FOR UPDATE SKIP
+    /// LOCKED` to skip past.
+    ///
+    /// That lock-free fencing - strengthened by [`Self::reclaim_process`] stamping a
+    /// fresh `started_at` on every reclaim - is also why this crate doesn't implement a
+    /// `SELECT ... FOR UPDATE SKIP LOCKED` row-claiming path: there's nothing for it to
+    /// add over the conditional write already in place. `tests/postgres_test.rs` exercises
+    /// the concurrent-claim, timeout-recovery, and multiple-processor contract against a
+    /// live Postgres the same way `tests/integration_test.rs`/`correctness_test.rs` do
+    /// against DynamoDB.
+    #[cfg_attr(feature = "tracing", instrument(skip(self)))]
+    pub async fn stale_records(
+        &self,
+        max_processing_time: Duration,
+    ) -> Result<Vec<(Id, ProcessorId)>, Error>
+    where
+        Id: DeserializeOwned,
+        ProcessorId: DeserializeOwned,
+    {
+        let cutoff = now_millis()? - max_processing_time.as_millis() as i64;
+
+        let rows = self
+            .client
+            .query(
+                &format!(
+                    "SELECT id, processor_id FROM {table}
+                     WHERE completed_at IS NULL AND COALESCE(heartbeat_at, started_at) < $1",
+                    table = self.table_name,
+                ),
+                &[&cutoff],
+            )
+            .await
+            .map_err(|e| Error::Postgres(e.to_string()))?;
+
+        rows.into_iter()
+            .map(|row| {
+                let id_str: String = row.get(0);
+                let processor_id_str: String = row.get(1);
+                Ok((serde_json::from_str(&id_str)?, serde_json::from_str(&processor_id_str)?))
+            })
+            .collect()
+    }
+}
+
+fn now_millis() -> Result<i64, Error> {
+    Ok(SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| Error::Internal(e.to_string()))?
+        .as_millis() as i64)
+}
+
+#[async_trait]
+impl<Id, ProcessorId, A> Persistence<Id, ProcessorId, A> for PostgresPersistence<Id, ProcessorId, A>
+where
+    Id: Serialize + DeserializeOwned + Send + Sync + Clone + 'static,
+    ProcessorId: Serialize + DeserializeOwned + Send + Sync + Clone + 'static,
+    A: Serialize + DeserializeOwned + Send + Sync + Clone + 'static,
+{
+    #[cfg_attr(feature = "tracing", instrument(skip(self, id, processor_id)))]
+    async fn start_processing_update(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        now: SystemTime,
+    ) -> Result<Option<Process<Id, ProcessorId, A>>, Error> {
+        let id_str = serde_json::to_string(&id)?;
+        let processor_id_str = serde_json::to_string(&processor_id)?;
+        let now_millis = now
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| Error::Internal(e.to_string()))?
+            .as_millis() as i64;
+
+        // `xmax = 0` is true only for the row version this statement itself inserted, so it
+        // distinguishes "brand new claim" from "conflicted with an existing row" in one
+        // round-trip. On conflict the SET is a no-op, so the RETURNING values are exactly
+        // the prior record - mirroring DynamoDB's `if_not_exists` + `ReturnValue::AllOld`.
+        let row = self
+            .client
+            .query_one(
+                &format!(
+                    "INSERT INTO {table} (id, processor_id, started_at)
+                     VALUES ($1, $2, $3)
+                     ON CONFLICT (id, processor_id) DO UPDATE SET started_at = {table}.started_at
+                     RETURNING started_at, completed_at, expires_on, memoized, heartbeat_at,
+                               failed_at, attempt, failure_reason, (xmax = 0) AS inserted",
+                    table = self.table_name,
+                ),
+                &[&id_str, &processor_id_str, &now_millis],
+            )
+            .await
+            .map_err(|e| Error::Postgres(e.to_string()))?;
+
+        let inserted: bool = row.get(8);
+        if inserted {
+            return Ok(None);
+        }
+
+        let started_at: i64 = row.get(0);
+        let completed_at: Option<i64> = row.get(1);
+        let expires_on: Option<i64> = row.get(2);
+        let memoized: Option<String> = row.get(3);
+        let heartbeat_at: Option<i64> = row.get(4);
+        let failed_at: Option<i64> = row.get(5);
+        let attempt: i32 = row.get(6);
+        let failure_reason: Option<String> = row.get(7);
+
+        Ok(Some(Process {
+            id,
+            processor_id,
+            started_at: UNIX_EPOCH + Duration::from_millis(started_at as u64),
+            completed_at: completed_at.map(|millis| UNIX_EPOCH + Duration::from_millis(millis as u64)),
+            expires_on: expires_on
+                .map(|secs| Expiration::new(UNIX_EPOCH + Duration::from_millis(secs as u64))),
+            last_heartbeat_at: heartbeat_at.map(|millis| UNIX_EPOCH + Duration::from_millis(millis as u64)),
+            failed_at: failed_at.map(|millis| UNIX_EPOCH + Duration::from_millis(millis as u64)),
+            attempt: attempt as u32,
+            failure_reason,
+            memoized: memoized.map(|s| serde_json::from_str(&s)).transpose()?,
+        }))
+    }
+
+    /// Unlike `start_processing_update`'s `ON CONFLICT ... DO UPDATE SET started_at =
+    /// started_at` no-op, this unconditionally overwrites `started_at` with `now` - but
+    /// only while it still equals `expected_claim_token`, so a concurrent reclaimer of
+    /// the same stale claim can't also win. Zero rows affected means someone else
+    /// already reclaimed it first.
+    #[cfg_attr(feature = "tracing", instrument(skip(self, id, processor_id)))]
+    async fn reclaim_process(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        now: SystemTime,
+        expected_claim_token: SystemTime,
+    ) -> Result<(), Error> {
+        let id_str = serde_json::to_string(&id)?;
+        let processor_id_str = serde_json::to_string(&processor_id)?;
+        let now_millis = now
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| Error::Internal(e.to_string()))?
+            .as_millis() as i64;
+        let expected_millis = expected_claim_token
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| Error::Internal(e.to_string()))?
+            .as_millis() as i64;
+
+        let rows_affected = self
+            .client
+            .execute(
+                &format!(
+                    "UPDATE {table} SET started_at = $3 WHERE id = $1 AND processor_id = $2 AND started_at = $4",
+                    table = self.table_name,
+                ),
+                &[&id_str, &processor_id_str, &now_millis, &expected_millis],
+            )
+            .await
+            .map_err(|e| Error::Postgres(e.to_string()))?;
+
+        if rows_affected == 0 {
+            return Err(Error::ClaimLost);
+        }
+
+        Ok(())
+    }
+
+    /// Refreshes `heartbeat_at`. A plain `UPDATE` naturally no-ops (affects zero rows,
+    /// which isn't an error) if the record was already invalidated (deleted), so there's
+    /// no conditional-write equivalent to worry about here; a completed record still
+    /// exists and has its `heartbeat_at` refreshed like any other. When `claim_token`
+    /// is set, the `UPDATE` is additionally guarded on `started_at` still matching it;
+    /// zero rows affected then means another processor has reclaimed the record, so the
+    /// heartbeat task should stop rather than treat it as a harmless no-op.
+    #[cfg_attr(feature = "tracing", instrument(skip(self, id, processor_id)))]
+    async fn heartbeat(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        now: SystemTime,
+        claim_token: Option<SystemTime>,
+    ) -> Result<(), Error> {
+        let id_str = serde_json::to_string(&id)?;
+        let processor_id_str = serde_json::to_string(&processor_id)?;
+        let now_millis = now
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| Error::Internal(e.to_string()))?
+            .as_millis() as i64;
+
+        let rows_affected = if let Some(token) = claim_token {
+            let token_millis = token
+                .duration_since(UNIX_EPOCH)
+                .map_err(|e| Error::Internal(e.to_string()))?
+                .as_millis() as i64;
+
+            self.client
+                .execute(
+                    &format!(
+                        "UPDATE {table} SET heartbeat_at = $3 WHERE id = $1 AND processor_id = $2 AND started_at = $4",
+                        table = self.table_name,
+                    ),
+                    &[&id_str, &processor_id_str, &now_millis, &token_millis],
+                )
+                .await
+                .map_err(|e| Error::Postgres(e.to_string()))?
+        } else {
+            self.client
+                .execute(
+                    &format!(
+                        "UPDATE {table} SET heartbeat_at = $3 WHERE id = $1 AND processor_id = $2",
+                        table = self.table_name,
+                    ),
+                    &[&id_str, &processor_id_str, &now_millis],
+                )
+                .await
+                .map_err(|e| Error::Postgres(e.to_string()))?
+        };
+
+        if claim_token.is_some() && rows_affected == 0 {
+            return Err(Error::ClaimLost);
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(feature = "tracing", instrument(skip(self, id, processor_id, value)))]
+    async fn complete_process(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        now: SystemTime,
+        ttl: Option<Duration>,
+        value: A,
+        claim_token: Option<SystemTime>,
+    ) -> Result<(), Error> {
+        let id_str = serde_json::to_string(&id)?;
+        let processor_id_str = serde_json::to_string(&processor_id)?;
+        let now_millis = now
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| Error::Internal(e.to_string()))?
+            .as_millis() as i64;
+        let memoized_str = serde_json::to_string(&value)?;
+        let expires_on = ttl
+            .map(|ttl| {
+                (now + ttl)
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_millis() as i64)
+                    .map_err(|e| Error::Internal(e.to_string()))
+            })
+            .transpose()?;
+
+        let rows_affected = if let Some(token) = claim_token {
+            let token_millis = token
+                .duration_since(UNIX_EPOCH)
+                .map_err(|e| Error::Internal(e.to_string()))?
+                .as_millis() as i64;
+
+            self.client
+                .execute(
+                    &format!(
+                        "UPDATE {table}
+                         SET completed_at = $3, memoized = $4, expires_on = $5
+                         WHERE id = $1 AND processor_id = $2 AND started_at = $6 AND completed_at IS NULL",
+                        table = self.table_name,
+                    ),
+                    &[
+                        &id_str,
+                        &processor_id_str,
+                        &now_millis,
+                        &memoized_str,
+                        &expires_on,
+                        &token_millis,
+                    ],
+                )
+                .await
+                .map_err(|e| Error::Postgres(e.to_string()))?
+        } else {
+            self.client
+                .execute(
+                    &format!(
+                        "UPDATE {table}
+                         SET completed_at = $3, memoized = $4, expires_on = $5
+                         WHERE id = $1 AND processor_id = $2",
+                        table = self.table_name,
+                    ),
+                    &[&id_str, &processor_id_str, &now_millis, &memoized_str, &expires_on],
+                )
+                .await
+                .map_err(|e| Error::Postgres(e.to_string()))?
+        };
+
+        if claim_token.is_some() && rows_affected == 0 {
+            return Err(Error::ClaimLost);
+        }
+
+        self.record_history(
+            &id_str,
+            &processor_id_str,
+            now_millis,
+            Some(now_millis),
+            Some(&memoized_str),
+            false,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    #[cfg_attr(feature = "tracing", instrument(skip(self, id, processor_id, reason)))]
+    async fn fail_process(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        now: SystemTime,
+        attempt: u32,
+        reason: String,
+        claim_token: Option<SystemTime>,
+    ) -> Result<(), Error> {
+        let id_str = serde_json::to_string(&id)?;
+        let processor_id_str = serde_json::to_string(&processor_id)?;
+        let now_millis = now
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| Error::Internal(e.to_string()))?
+            .as_millis() as i64;
+        let attempt = attempt as i32;
+
+        let rows_affected = if let Some(token) = claim_token {
+            let token_millis = token
+                .duration_since(UNIX_EPOCH)
+                .map_err(|e| Error::Internal(e.to_string()))?
+                .as_millis() as i64;
+
+            self.client
+                .execute(
+                    &format!(
+                        "UPDATE {table}
+                         SET failed_at = $3, attempt = $4, failure_reason = $5
+                         WHERE id = $1 AND processor_id = $2 AND started_at = $6 AND completed_at IS NULL",
+                        table = self.table_name,
+                    ),
+                    &[&id_str, &processor_id_str, &now_millis, &attempt, &reason, &token_millis],
+                )
+                .await
+                .map_err(|e| Error::Postgres(e.to_string()))?
+        } else {
+            self.client
+                .execute(
+                    &format!(
+                        "UPDATE {table}
+                         SET failed_at = $3, attempt = $4, failure_reason = $5
+                         WHERE id = $1 AND processor_id = $2",
+                        table = self.table_name,
+                    ),
+                    &[&id_str, &processor_id_str, &now_millis, &attempt, &reason],
+                )
+                .await
+                .map_err(|e| Error::Postgres(e.to_string()))?
+        };
+
+        if claim_token.is_some() && rows_affected == 0 {
+            return Err(Error::ClaimLost);
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(feature = "tracing", instrument(skip(self, id, processor_id)))]
+    async fn invalidate_process(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        claim_token: Option<SystemTime>,
+    ) -> Result<(), Error> {
+        let id_str = serde_json::to_string(&id)?;
+        let processor_id_str = serde_json::to_string(&processor_id)?;
+
+        let rows_affected = if let Some(token) = claim_token {
+            let token_millis = token
+                .duration_since(UNIX_EPOCH)
+                .map_err(|e| Error::Internal(e.to_string()))?
+                .as_millis() as i64;
+
+            self.client
+                .execute(
+                    &format!(
+                        "DELETE FROM {table} WHERE id = $1 AND processor_id = $2 AND started_at = $3",
+                        table = self.table_name,
+                    ),
+                    &[&id_str, &processor_id_str, &token_millis],
+                )
+                .await
+                .map_err(|e| Error::Postgres(e.to_string()))?
+        } else {
+            self.client
+                .execute(
+                    &format!(
+                        "DELETE FROM {table} WHERE id = $1 AND processor_id = $2",
+                        table = self.table_name,
+                    ),
+                    &[&id_str, &processor_id_str],
+                )
+                .await
+                .map_err(|e| Error::Postgres(e.to_string()))?
+        };
+
+        if claim_token.is_some() && rows_affected == 0 {
+            return Err(Error::ClaimLost);
+        }
+
+        self.record_history(&id_str, &processor_id_str, now_millis()?, None, None, true)
+            .await?;
+
+        Ok(())
+    }
+}
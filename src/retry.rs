@@ -0,0 +1,210 @@
+use crate::error::Error;
+use crate::model::Process;
+use crate::persistence::Persistence;
+use async_trait::async_trait;
+use rand::Rng;
+use std::marker::PhantomData;
+use std::time::{Duration, SystemTime};
+use tokio::time::sleep;
+
+/// Backoff parameters for [`RetryingPersistence`].
+///
+/// The delay before attempt `k` is `min(max_delay, base_delay * multiplier^k)`, then
+/// scaled by a random factor in `[0, 1]` (full jitter) to avoid synchronized retries
+/// across processes hammering the backend at the same moment.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub multiplier: f64,
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: u32, base_delay: Duration, max_delay: Duration, multiplier: f64) -> Self {
+        Self {
+            max_attempts,
+            base_delay,
+            max_delay,
+            multiplier,
+        }
+    }
+
+    fn delay_for_attempt(&self, attempt: u32) -> Duration {
+        let uncapped = self.base_delay.as_secs_f64() * self.multiplier.powi(attempt as i32);
+        let capped = uncapped.min(self.max_delay.as_secs_f64());
+        let jittered = capped * rand::thread_rng().gen_range(0.0..=1.0);
+        Duration::from_secs_f64(jittered)
+    }
+}
+
+/// Decorates a [`Persistence`] backend with retries for transient errors.
+///
+/// Each trait method loops up to `policy.max_attempts` times with exponential backoff
+/// and full jitter between tries, retrying only the errors that `is_transient` accepts.
+/// Conditional-check failures (the duplicate-detection signal backends rely on) are not
+/// transient and must propagate on the first attempt.
+pub struct RetryingPersistence<P, Id, ProcessorId, A> {
+    inner: P,
+    policy: RetryPolicy,
+    is_transient: fn(&Error) -> bool,
+    _marker: PhantomData<(Id, ProcessorId, A)>,
+}
+
+/// Default transient classifier: backend connectivity/throttling errors are retried,
+/// everything else (decoding, expiry, internal invariants) is surfaced immediately.
+pub fn default_is_transient(err: &Error) -> bool {
+    matches!(err, Error::DynamoDb(_) | Error::Postgres(_))
+}
+
+impl<P, Id, ProcessorId, A> RetryingPersistence<P, Id, ProcessorId, A> {
+    pub fn new(inner: P, policy: RetryPolicy) -> Self {
+        Self::with_classifier(inner, policy, default_is_transient)
+    }
+
+    pub fn with_classifier(inner: P, policy: RetryPolicy, is_transient: fn(&Error) -> bool) -> Self {
+        Self {
+            inner,
+            policy,
+            is_transient,
+            _marker: PhantomData,
+        }
+    }
+
+    async fn retrying<F, Fut, T>(&self, op: F) -> Result<T, Error>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T, Error>>,
+    {
+        let mut attempt = 0;
+
+        loop {
+            match op().await {
+                Ok(value) => return Ok(value),
+                Err(err) if attempt + 1 < self.policy.max_attempts && (self.is_transient)(&err) => {
+                    sleep(self.policy.delay_for_attempt(attempt)).await;
+                    attempt += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<P, Id, ProcessorId, A> Persistence<Id, ProcessorId, A> for RetryingPersistence<P, Id, ProcessorId, A>
+where
+    P: Persistence<Id, ProcessorId, A>,
+    Id: Clone + Send + Sync + 'static,
+    ProcessorId: Clone + Send + Sync + 'static,
+    A: Clone + Send + Sync + 'static,
+{
+    async fn start_processing_update(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        now: SystemTime,
+    ) -> Result<Option<Process<Id, ProcessorId, A>>, Error> {
+        self.retrying(|| {
+            self.inner
+                .start_processing_update(id.clone(), processor_id.clone(), now)
+        })
+        .await
+    }
+
+    async fn reclaim_process(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        now: SystemTime,
+        expected_claim_token: SystemTime,
+    ) -> Result<(), Error> {
+        // Same rationale as `complete_process` below: a `ClaimLost` loss of the reclaim
+        // race should surface immediately rather than being retried as if transient.
+        self.retrying(|| {
+            self.inner
+                .reclaim_process(id.clone(), processor_id.clone(), now, expected_claim_token)
+        })
+        .await
+    }
+
+    async fn complete_process(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        now: SystemTime,
+        ttl: Option<Duration>,
+        value: A,
+        claim_token: Option<SystemTime>,
+    ) -> Result<(), Error> {
+        // `default_is_transient` only accepts DynamoDb/Postgres errors, so a
+        // `ClaimLost` fencing failure surfaces on the first attempt rather than
+        // being retried.
+        self.retrying(|| {
+            self.inner
+                .complete_process(id.clone(), processor_id.clone(), now, ttl, value.clone(), claim_token)
+        })
+        .await
+    }
+
+    async fn invalidate_process(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        claim_token: Option<SystemTime>,
+    ) -> Result<(), Error> {
+        self.retrying(|| self.inner.invalidate_process(id.clone(), processor_id.clone(), claim_token))
+            .await
+    }
+
+    async fn fail_process(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        now: SystemTime,
+        attempt: u32,
+        reason: String,
+        claim_token: Option<SystemTime>,
+    ) -> Result<(), Error> {
+        self.retrying(|| {
+            self.inner
+                .fail_process(id.clone(), processor_id.clone(), now, attempt, reason.clone(), claim_token)
+        })
+        .await
+    }
+
+    async fn heartbeat(
+        &self,
+        id: Id,
+        processor_id: ProcessorId,
+        now: SystemTime,
+        claim_token: Option<SystemTime>,
+    ) -> Result<(), Error> {
+        self.retrying(|| {
+            self.inner
+                .heartbeat(id.clone(), processor_id.clone(), now, claim_token)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay_for_attempt_is_capped() {
+        let policy = RetryPolicy::new(5, Duration::from_millis(100), Duration::from_secs(1), 2.0);
+        for attempt in 0..10 {
+            assert!(policy.delay_for_attempt(attempt) <= Duration::from_secs(1));
+        }
+    }
+
+    #[test]
+    fn test_default_is_transient_classifies_backend_errors() {
+        assert!(default_is_transient(&Error::DynamoDb("throttled".to_string())));
+        assert!(default_is_transient(&Error::Postgres("timeout".to_string())));
+        assert!(!default_is_transient(&Error::Timeout));
+        assert!(!default_is_transient(&Error::Expired));
+    }
+}
@@ -107,6 +107,44 @@ impl EmailService {
         let hash = data.bytes().fold(0u128, |acc, b| acc.wrapping_mul(31).wrapping_add(b as u128));
         Uuid::from_u128(hash)
     }
+
+    /// Example that observes duplicate hits via `on_duplicate`
+    #[protect(mnemosyne = self.mnemosyne.clone(), id = email.id, on_duplicate = Self::log_duplicate_email)]
+    async fn send_email_logging_duplicates(&self, email: Email) -> Result<String, Error> {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        Ok(format!(
+            "Email sent to {} with subject: {}",
+            email.recipient, email.subject
+        ))
+    }
+
+    fn log_duplicate_email(value: String) -> Result<String, Error> {
+        println!("Duplicate send suppressed, returning memoized result: {value}");
+        Ok(value)
+    }
+}
+
+// Example struct using a composite id, so distinct tenants sending to the same email id
+// are deduplicated independently of one another.
+struct TenantEmailService {
+    mnemosyne: Arc<Mnemosyne<(Uuid, Uuid), Uuid, String>>,
+}
+
+impl TenantEmailService {
+    fn new(mnemosyne: Arc<Mnemosyne<(Uuid, Uuid), Uuid, String>>) -> Self {
+        Self { mnemosyne }
+    }
+
+    /// Composite id built from two fields via `id = [..]`, so the same `email.id` sent by
+    /// different tenants is deduplicated per-tenant rather than colliding.
+    #[protect(mnemosyne = self.mnemosyne.clone(), id = [tenant_id, email.id])]
+    async fn send_email_for_tenant(&self, tenant_id: Uuid, email: Email) -> Result<String, Error> {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        Ok(format!(
+            "Email sent to {} with subject: {}",
+            email.recipient, email.subject
+        ))
+    }
 }
 
 #[tokio::test]
@@ -230,3 +268,81 @@ async fn test_protect_macro_different_ids() {
 
     delete_test_table(&client, &table_name).await;
 }
+
+#[tokio::test]
+async fn test_protect_macro_composite_id() {
+    let client = create_test_client().await;
+    let table_name = format!("test-macro-composite-{}", Uuid::new_v4());
+
+    create_test_table(&client, &table_name).await;
+
+    let persistence = Arc::new(DynamoDbPersistence::new(client.clone(), table_name.clone()));
+
+    let config = Config::new(
+        Uuid::new_v4(),
+        Duration::from_secs(60),
+        Some(Duration::from_secs(3600)),
+        PollStrategy::linear(Duration::from_millis(100), Duration::from_secs(10)),
+    );
+
+    let mnemosyne = Arc::new(Mnemosyne::new(persistence, config));
+    let service = TenantEmailService::new(mnemosyne);
+
+    let tenant_a = Uuid::new_v4();
+    let tenant_b = Uuid::new_v4();
+    let email = Email {
+        id: Uuid::new_v4(),
+        recipient: "test@example.com".to_string(),
+        subject: "Test Subject".to_string(),
+        body: "Test Body".to_string(),
+    };
+
+    // Same email.id, different tenants: composite key keeps them independent
+    let result_a1 = service.send_email_for_tenant(tenant_a, email.clone()).await.unwrap();
+    let result_b1 = service.send_email_for_tenant(tenant_b, email.clone()).await.unwrap();
+    assert!(result_a1.contains("test@example.com"));
+    assert!(result_b1.contains("test@example.com"));
+
+    // Same tenant, same email.id again: deduped to the memoized result
+    let result_a2 = service.send_email_for_tenant(tenant_a, email.clone()).await.unwrap();
+    assert_eq!(result_a1, result_a2);
+
+    delete_test_table(&client, &table_name).await;
+}
+
+#[tokio::test]
+async fn test_protect_macro_on_duplicate_hook() {
+    let client = create_test_client().await;
+    let table_name = format!("test-macro-on-duplicate-{}", Uuid::new_v4());
+
+    create_test_table(&client, &table_name).await;
+
+    let persistence = Arc::new(DynamoDbPersistence::new(client.clone(), table_name.clone()));
+
+    let config = Config::new(
+        Uuid::new_v4(),
+        Duration::from_secs(60),
+        Some(Duration::from_secs(3600)),
+        PollStrategy::linear(Duration::from_millis(100), Duration::from_secs(10)),
+    );
+
+    let mnemosyne = Arc::new(Mnemosyne::new(persistence, config));
+    let service = EmailService::new(mnemosyne);
+
+    let email = Email {
+        id: Uuid::new_v4(),
+        recipient: "test@example.com".to_string(),
+        subject: "Test Subject".to_string(),
+        body: "Test Body".to_string(),
+    };
+
+    // First call executes fresh.
+    let result1 = service.send_email_logging_duplicates(email.clone()).await.unwrap();
+    assert!(result1.contains("test@example.com"));
+
+    // Second call routes through `log_duplicate_email`, which returns the memoized value.
+    let result2 = service.send_email_logging_duplicates(email.clone()).await.unwrap();
+    assert_eq!(result1, result2);
+
+    delete_test_table(&client, &table_name).await;
+}